@@ -0,0 +1,73 @@
+#[macro_use]
+extern crate criterion;
+extern crate fbas_analyzer;
+
+use criterion::black_box;
+use criterion::Criterion;
+
+use std::path::Path;
+
+use fbas_analyzer::*;
+
+/// Deterministically generates a tiered FBAS: a top tier of `n_orgs` organizations with
+/// `nodes_per_org` nodes each, where each top-tier node requires a 2/3 majority of organizations
+/// (each of which in turn requires a simple majority of its nodes), plus `n_watchers` non-top-tier
+/// nodes that use the same quorum set.
+fn generate_tiered_fbas(n_orgs: usize, nodes_per_org: usize, n_watchers: usize) -> Fbas {
+    let n_top_tier = n_orgs * nodes_per_org;
+    let inner_quorum_sets: Vec<QuorumSet> = (0..n_top_tier)
+        .step_by(nodes_per_org)
+        .map(|first| QuorumSet {
+            threshold: nodes_per_org / 2 + 1,
+            validators: (first..first + nodes_per_org).collect(),
+            inner_quorum_sets: vec![],
+        })
+        .collect();
+    let quorum_set = QuorumSet {
+        threshold: 2 * n_orgs / 3 + 1,
+        validators: vec![],
+        inner_quorum_sets,
+    };
+    let mut fbas = Fbas::new();
+    for _ in 0..n_top_tier + n_watchers {
+        fbas.add_generic_node(quorum_set.clone());
+    }
+    fbas
+}
+
+fn bench_finders(c: &mut Criterion, label: &str, fbas: &Fbas) {
+    c.bench_function(&format!("find_minimal_quorums_{}", label), |b| {
+        b.iter(|| find_minimal_quorums(black_box(fbas)))
+    });
+    c.bench_function(&format!("find_minimal_blocking_sets_{}", label), |b| {
+        b.iter(|| find_minimal_blocking_sets(black_box(fbas)))
+    });
+    // splitting set search on non-core nodes is prohibitively slow
+    let fbas_core = fbas.to_core();
+    c.bench_function(
+        &format!("find_minimal_splitting_sets_{}_core_only", label),
+        |b| b.iter(|| find_minimal_splitting_sets(black_box(&fbas_core))),
+    );
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let fbas_correct = Fbas::from_json_file(Path::new("test_data/correct.json"));
+    bench_finders(c, "correct", &fbas_correct);
+
+    let fbas_generated = generate_tiered_fbas(7, 3, 30);
+    bench_finders(c, "generated_tiered", &fbas_generated);
+
+    // Slow; run with `cargo bench --features slow-benchmarks`
+    if cfg!(feature = "slow-benchmarks") {
+        let fbas_stellarbeat =
+            Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        bench_finders(c, "stellarbeat_2019", &fbas_stellarbeat);
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = criterion_benchmark
+}
+criterion_main!(benches);
@@ -37,6 +37,11 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         },
     );
 
+    // many node IDs, most of them irrelevant; guards against per-set allocation overhead
+    c.bench_function("find_minimal_quorums_not_in_standard_form", |b| {
+        b.iter(|| find_minimal_quorums(black_box(&fbas)))
+    });
+
     let fbas = fbas.to_standard_form();
     let fbas_stt = fbas_stt.to_standard_form();
 
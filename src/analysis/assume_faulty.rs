@@ -1,9 +1,29 @@
+//! Transformations for modelling faulty nodes while keeping all node IDs unchanged.
+//!
+//! Crash-faulty nodes (see [`Fbas::assume_crash_faulty`]) stop participating: they can no longer
+//! be part of any quorum, but stay in other nodes' quorum sets, where they now count against the
+//! thresholds. Split-faulty nodes (see [`Fbas::assume_split_faulty`]) may behave inconsistently
+//! towards different peers: they are additionally redacted from all quorum sets (with thresholds
+//! lowered accordingly), as correct nodes can't rely on them for (dis)agreement.
+
 use super::*;
 
+/// Fault model for analyses that support more than one; see, e.g.,
+/// [`Analysis::minimal_blocking_sets_with_model`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaultModel {
+    /// Faulty nodes stop participating; see [`Fbas::assume_crash_faulty`].
+    Crash,
+    /// Faulty nodes may additionally behave inconsistently towards different peers; see
+    /// [`Fbas::assume_split_faulty`].
+    Byzantine,
+}
+
 impl Fbas {
     /// Assume in the following that `nodes` can exhibit crash failures with the "goal" of
     /// blocking individual nodes or the whole FBAS. For keeping node IDs unchanged, this method
-    /// doesn't delete the node entirely but only makes it unsatisfiable.
+    /// doesn't delete the node entirely but only makes it unsatisfiable; it stays in other
+    /// nodes' quorum sets. For a non-mutating variant see [`Fbas::with_crash_faulty`].
     pub fn assume_crash_faulty(&mut self, nodes: &NodeIdSet) {
         for node_id in nodes.iter() {
             self.nodes[node_id].quorum_set = QuorumSet::new_unsatisfiable();
@@ -12,7 +32,8 @@ impl Fbas {
     /// Assume in the following that `nodes` can exhibit Byzantine failures with the "goal" of
     /// provoking splits. This corresponds to the *delete* operation from Mazières's original
     /// FBAS/SCP paper. For keeping node IDs unchanged, this method doesn't delete the node
-    /// entirely but only makes it unsatisfiable and redacts it from all quorum sets.
+    /// entirely but only makes it unsatisfiable and redacts it from all quorum sets. For a
+    /// non-mutating variant see [`Fbas::with_split_faulty`].
     pub fn assume_split_faulty(&mut self, nodes: &NodeIdSet) {
         for node_id in nodes.iter() {
             self.nodes[node_id].quorum_set = QuorumSet::new_unsatisfiable();
@@ -21,6 +42,30 @@ impl Fbas {
             node.assume_split_faulty(nodes);
         }
     }
+    /// Copy of this FBAS in which `nodes` are crash-faulty; see [`Fbas::assume_crash_faulty`].
+    pub fn with_crash_faulty(&self, nodes: &NodeIdSet) -> Self {
+        let mut fbas = self.clone();
+        fbas.assume_crash_faulty(nodes);
+        fbas
+    }
+    /// Copy of this FBAS in which `nodes` are split-faulty; see [`Fbas::assume_split_faulty`].
+    pub fn with_split_faulty(&self, nodes: &NodeIdSet) -> Self {
+        let mut fbas = self.clone();
+        fbas.assume_split_faulty(nodes);
+        fbas
+    }
+    /// Like [`Fbas::assume_crash_faulty`], but with nodes referred to by their public keys.
+    /// Unknown public keys are ignored.
+    pub fn assume_crash_faulty_pretty(&mut self, nodes: &[PublicKey]) {
+        let nodes = self.node_ids_of(nodes);
+        self.assume_crash_faulty(&nodes);
+    }
+    /// Like [`Fbas::assume_split_faulty`], but with nodes referred to by their public keys.
+    /// Unknown public keys are ignored.
+    pub fn assume_split_faulty_pretty(&mut self, nodes: &[PublicKey]) {
+        let nodes = self.node_ids_of(nodes);
+        self.assume_split_faulty(&nodes);
+    }
 }
 impl Node {
     /// This corresponds to the *delete* operation from Mazières's original FBAS/SCP paper.
@@ -32,12 +77,7 @@ impl QuorumSet {
     /// This corresponds to the *delete* operation from Mazières's original FBAS/SCP paper.
     pub fn assume_split_faulty(&mut self, nodes: &NodeIdSet) {
         let n_validators_before = self.validators.len();
-        self.validators = self
-            .validators
-            .iter()
-            .copied()
-            .filter(|&x| !nodes.contains(x))
-            .collect();
+        self.validators.retain(|&x| !nodes.contains(x));
         let n_validator_deletions = n_validators_before - self.validators.len();
 
         for iqs in self.inner_quorum_sets.iter_mut() {
@@ -121,6 +161,93 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn crash_faulty_nodes_block_quorums_they_are_needed_for() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            }
+        ]"#,
+        );
+        let crashed = fbas.with_crash_faulty(&bitset! {2});
+
+        assert!(fbas.is_quorum(&bitset! {0, 2}));
+        assert!(!crashed.is_quorum(&bitset! {0, 2}));
+        assert!(!crashed.is_quorum(&bitset! {0, 1, 2}));
+        assert!(crashed.is_quorum(&bitset! {0, 1}));
+        assert!(!crashed.is_quorum(&bitset! {0}));
+    }
+
+    #[test]
+    fn split_faulty_nodes_are_no_longer_needed_for_quorums() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            }
+        ]"#,
+        );
+        let split = fbas.with_split_faulty(&bitset! {2});
+
+        assert!(!fbas.is_quorum(&bitset! {0}));
+        assert!(split.is_quorum(&bitset! {0}));
+        assert!(split.is_quorum(&bitset! {1}));
+        assert!(!split.is_quorum(&bitset! {0, 2}));
+        assert_eq!(fbas, fbas.with_split_faulty(&bitset! {}));
+    }
+
+    #[test]
+    fn assume_faulty_pretty_equals_assume_faulty_by_id() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            }
+        ]"#,
+        );
+        let pretty_nodes = [String::from("n1"), String::from("Helen the non-existent")];
+
+        let mut expected = fbas.clone();
+        expected.assume_crash_faulty(&bitset! {1});
+        let mut actual = fbas.clone();
+        actual.assume_crash_faulty_pretty(&pretty_nodes);
+        assert_eq!(expected, actual);
+
+        let mut expected = fbas.clone();
+        expected.assume_split_faulty(&bitset! {1});
+        let mut actual = fbas;
+        actual.assume_split_faulty_pretty(&pretty_nodes);
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn assume_split_faulty_works_on_a_more_complex_fbas() {
         let mut fbas = Fbas::from_json_str(
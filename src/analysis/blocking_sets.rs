@@ -4,8 +4,18 @@ use itertools::Itertools;
 
 /// Find all minimal blocking sets in the FBAS.
 pub fn find_minimal_blocking_sets(fbas: &Fbas) -> Vec<NodeIdSet> {
+    find_minimal_blocking_sets_with_cache(fbas, &SymmetricNodesCache::default())
+}
+
+/// Like `find_minimal_blocking_sets`, but reusing (and filling) `symmetric_nodes_cache`.
+pub(crate) fn find_minimal_blocking_sets_with_cache(
+    fbas: &Fbas,
+    symmetric_nodes_cache: &SymmetricNodesCache,
+) -> Vec<NodeIdSet> {
     info!("Starting to look for minimal blocking_sets...");
-    let minimal_blocking_sets = find_minimal_sets(fbas, minimal_blocking_sets_finder);
+    let minimal_blocking_sets = find_minimal_sets(fbas, |clusters, fbas| {
+        bounded_minimal_blocking_sets_finder(clusters, fbas, usize::MAX, symmetric_nodes_cache)
+    });
     info!(
         "Found {} minimal blocking_sets.",
         minimal_blocking_sets.len()
@@ -13,7 +23,36 @@ pub fn find_minimal_blocking_sets(fbas: &Fbas) -> Vec<NodeIdSet> {
     minimal_blocking_sets
 }
 
-fn minimal_blocking_sets_finder(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas) -> Vec<NodeIdSet> {
+/// Find all minimal blocking sets in the FBAS that consist of at most `max_size` nodes. Prunes
+/// the search early, which makes this much faster than `find_minimal_blocking_sets` for small
+/// `max_size` and large FBASs.
+pub fn find_minimal_blocking_sets_up_to_size(fbas: &Fbas, max_size: usize) -> Vec<NodeIdSet> {
+    info!(
+        "Starting to look for minimal blocking_sets of size {} or smaller...",
+        max_size
+    );
+    let minimal_blocking_sets = find_minimal_sets(fbas, |clusters, fbas| {
+        bounded_minimal_blocking_sets_finder(
+            clusters,
+            fbas,
+            max_size,
+            &SymmetricNodesCache::default(),
+        )
+    });
+    info!(
+        "Found {} minimal blocking_sets of size {} or smaller.",
+        minimal_blocking_sets.len(),
+        max_size
+    );
+    minimal_blocking_sets
+}
+
+fn bounded_minimal_blocking_sets_finder(
+    consensus_clusters: Vec<NodeIdSet>,
+    fbas: &Fbas,
+    max_size: usize,
+    symmetric_nodes_cache: &SymmetricNodesCache,
+) -> Vec<NodeIdSet> {
     let mut found_blocking_sets_per_cluster: Vec<Vec<NodeIdSet>> = vec![];
     for (i, nodes) in consensus_clusters.into_iter().enumerate() {
         debug!("Finding minimal blocking sets in cluster {}...", i);
@@ -22,14 +61,20 @@ fn minimal_blocking_sets_finder(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas)
             is_symmetric_cluster(&nodes, &fbas.with_standard_form_quorum_sets())
         {
             debug!("Cluster contains a symmetric quorum cluster! Extracting blocking sets...");
-            found_blocking_sets_per_cluster.push(symmetric_cluster.to_minimal_blocking_sets(fbas));
+            found_blocking_sets_per_cluster.push(
+                symmetric_cluster
+                    .to_minimal_blocking_sets(fbas)
+                    .into_iter()
+                    .filter(|blocking_set| blocking_set.len() <= max_size)
+                    .collect(),
+            );
         } else {
             debug!("Sorting nodes by rank...");
             let sorted_nodes = sort_by_rank(nodes.iter().collect(), fbas);
             debug!("Sorted.");
 
             debug!("Looking for symmetric nodes...");
-            let symmetric_nodes = find_symmetric_nodes_in_node_set(&nodes, fbas);
+            let symmetric_nodes = symmetric_nodes_cache.get_or_find(&nodes, fbas);
             debug!("Done.");
 
             let mut found_unexpanded_blocking_sets_in_this_cluster: Vec<NodeIdSet> = vec![];
@@ -38,7 +83,7 @@ fn minimal_blocking_sets_finder(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas)
             minimal_blocking_sets_finder_step(
                 &mut CandidateValues::new(sorted_nodes),
                 &mut found_unexpanded_blocking_sets_in_this_cluster,
-                &FbasValues::new(fbas, &symmetric_nodes),
+                &FbasValues::new(fbas, &symmetric_nodes, max_size),
                 true,
             );
             let found_blocking_sets =
@@ -57,6 +102,7 @@ fn minimal_blocking_sets_finder(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas)
             }
             combined_blocking_set
         })
+        .filter(|blocking_set| blocking_set.len() <= max_size)
         .collect()
 }
 fn minimal_blocking_sets_finder_step(
@@ -80,9 +126,10 @@ fn minimal_blocking_sets_finder_step(
         // We require that symmetric nodes are used in a fixed order; this way we can omit
         // redundant branches (we expand all combinations of symmetric nodes in the final result
         // sets).
-        if fbas_values
-            .symmetric_nodes
-            .is_non_redundant_next(current_candidate, &candidates.selection)
+        if candidates.selection.len() < fbas_values.max_size
+            && fbas_values
+                .symmetric_nodes
+                .is_non_redundant_next(current_candidate, &candidates.selection)
         {
             candidates.selection.insert(current_candidate);
             candidates.remaining.remove(current_candidate);
@@ -130,12 +177,15 @@ impl CandidateValues {
 struct FbasValues<'a> {
     fbas: &'a Fbas,
     symmetric_nodes: &'a SymmetricNodesMap,
+    // blocking sets with more nodes are not searched for
+    max_size: usize,
 }
 impl<'a> FbasValues<'a> {
-    fn new(fbas: &'a Fbas, symmetric_nodes: &'a SymmetricNodesMap) -> Self {
+    fn new(fbas: &'a Fbas, symmetric_nodes: &'a SymmetricNodesMap, max_size: usize) -> Self {
         Self {
             fbas,
             symmetric_nodes,
+            max_size,
         }
     }
 }
@@ -155,9 +205,6 @@ impl QuorumSet {
     fn to_blocking_sets(&self) -> Vec<NodeIdSet> {
         self.to_slices(|qset| qset.blocking_threshold())
     }
-    fn blocking_threshold(&self) -> usize {
-        (self.validators.len() + self.inner_quorum_sets.len() + 1).wrapping_sub(self.threshold)
-    }
 }
 
 fn is_minimal_for_blocking_set(blocking_set: &NodeIdSet, fbas: &Fbas) -> bool {
@@ -200,6 +247,22 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn bounded_minimal_blocking_sets_equal_filtered_minimal_blocking_sets() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let all_blocking_sets = find_minimal_blocking_sets(&fbas);
+
+        for max_size in 0..4 {
+            let expected: Vec<NodeIdSet> = all_blocking_sets
+                .iter()
+                .filter(|blocking_set| blocking_set.len() <= max_size)
+                .cloned()
+                .collect();
+            let actual = find_minimal_blocking_sets_up_to_size(&fbas, max_size);
+            assert_eq!(expected, actual);
+        }
+    }
+
     #[test]
     fn minimal_blocking_sets_in_broken_trivial() {
         let fbas = Fbas::from_json_file(Path::new("test_data/broken_trivial.json"));
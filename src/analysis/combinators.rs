@@ -0,0 +1,170 @@
+//! Composable transforms for post-processing analysis results, in addition to the built-in ones
+//! like `merged_by_group`, `minimal_sets` and `without_nodes`. All transforms work on original
+//! (unshrunken) node IDs and return new results, so that they can be chained.
+
+use super::*;
+use std::borrow::Borrow;
+
+impl NodeIdSetResult {
+    /// Apply an arbitrary transform to the contained node set.
+    pub fn map_set(&self, transform: impl FnOnce(NodeIdSet) -> NodeIdSet) -> Self {
+        Self {
+            node_set: transform(self.node_set.clone()),
+        }
+    }
+    /// Keep only nodes that are also contained in `mask`, which can be a `NodeIdSet` or another
+    /// `NodeIdSetResult`.
+    #[doc(alias = "intersection")]
+    pub fn intersected_with(&self, mask: &impl Borrow<NodeIdSet>) -> Self {
+        self.map_set(|mut node_set| {
+            node_set.intersect_with(mask.borrow());
+            node_set
+        })
+    }
+    /// Remove all nodes contained in `other`, which can be a `NodeIdSet` or another
+    /// `NodeIdSetResult`.
+    pub fn difference(&self, other: &impl Borrow<NodeIdSet>) -> Self {
+        self.map_set(|mut node_set| {
+            node_set.difference_with(other.borrow());
+            node_set
+        })
+    }
+    /// Add all nodes contained in `other`, which can be a `NodeIdSet` or another
+    /// `NodeIdSetResult`.
+    pub fn union(&self, other: &impl Borrow<NodeIdSet>) -> Self {
+        self.map_set(|mut node_set| {
+            node_set.union_with(other.borrow());
+            node_set
+        })
+    }
+}
+
+impl NodeIdSetVecResult {
+    /// Apply an arbitrary transform to each contained node set. If all resulting node IDs were
+    /// already part of the result before, the (memory-saving) shrunken representation is kept.
+    pub fn map_sets(&self, transform: impl Fn(NodeIdSet) -> NodeIdSet) -> Self {
+        let transformed_node_sets: Vec<NodeIdSet> = self
+            .unshrunken_node_sets()
+            .into_iter()
+            .map(transform)
+            .collect();
+        if let Some(unshrink_table) = &self.unshrink_table {
+            let shrink_map: HashMap<NodeId, NodeId> = unshrink_table
+                .iter()
+                .enumerate()
+                .map(|(shrunken_id, &original_id)| (original_id, shrunken_id))
+                .collect();
+            if transformed_node_sets
+                .iter()
+                .all(|node_set| node_set.iter().all(|id| shrink_map.contains_key(&id)))
+            {
+                return Self {
+                    shrunken_node_sets: shrink_sets(&transformed_node_sets, &shrink_map),
+                    unshrink_table: self.unshrink_table.clone(),
+                };
+            }
+        }
+        Self::new(transformed_node_sets, None)
+    }
+    /// Keep only the node sets for which `predicate` returns `true`.
+    pub fn filter_sets(&self, predicate: impl Fn(&NodeIdSet) -> bool) -> Self {
+        let unshrink_table = self.unshrink_table.as_deref();
+        Self {
+            shrunken_node_sets: self
+                .shrunken_node_sets
+                .iter()
+                .filter(|&node_set| {
+                    if let Some(unshrink_table) = unshrink_table {
+                        predicate(&unshrink_set(node_set, unshrink_table))
+                    } else {
+                        predicate(node_set)
+                    }
+                })
+                .cloned()
+                .collect(),
+            unshrink_table: self.unshrink_table.clone(),
+        }
+    }
+    /// Keep only nodes that are also contained in `mask`, in each node set. Might result in
+    /// empty or non-minimal node sets.
+    pub fn intersected_with(&self, mask: &NodeIdSet) -> Self {
+        self.map_sets(|mut node_set| {
+            node_set.intersect_with(mask);
+            node_set
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shrunken_result() -> NodeIdSetVecResult {
+        let shrink_manager = ShrinkManager::new(bitset![3, 5, 7, 9]);
+        NodeIdSetVecResult::new(bitsetvec![{0, 1}, {1, 2, 3}, {2}], Some(&shrink_manager))
+    }
+
+    #[test]
+    fn map_sets_with_custom_mask_transform() {
+        let result = shrunken_result();
+        let mask = bitset![5, 7];
+
+        let expected = bitsetvec![{5}, {5, 7}, {7}];
+        let actual = result.map_sets(|mut node_set| {
+            node_set.intersect_with(&mask);
+            node_set
+        });
+        assert_eq!(expected, actual.clone().unwrap());
+        assert!(actual.unshrink_table.is_some());
+        assert_eq!(actual, result.intersected_with(&mask));
+    }
+
+    #[test]
+    fn map_sets_can_introduce_new_nodes() {
+        let result = shrunken_result();
+
+        let expected = bitsetvec![{3, 5, 42}, {5, 7, 9, 42}, {7, 42}];
+        let actual = result.map_sets(|mut node_set| {
+            node_set.insert(42);
+            node_set
+        });
+        assert_eq!(expected, actual.unwrap());
+    }
+
+    #[test]
+    fn filter_sets_sees_original_node_ids() {
+        let result = shrunken_result();
+
+        let expected = bitsetvec![{5, 7, 9}];
+        let actual = result.filter_sets(|node_set| node_set.contains(9));
+        assert_eq!(expected, actual.unwrap());
+    }
+
+    #[test]
+    fn set_algebra_on_set_result() {
+        let shrink_manager = ShrinkManager::new(bitset![3, 5, 7, 9]);
+        let result = NodeIdSetResult::new(bitset![0, 1, 2], Some(&shrink_manager));
+        let other = NodeIdSetResult::new(bitset![5, 9], None);
+
+        assert_eq!(bitset![3, 7], result.difference(&other).unwrap());
+        assert_eq!(bitset![5], result.intersected_with(&other).unwrap());
+        assert_eq!(bitset![3, 5, 7, 9], result.union(&other).unwrap());
+
+        let other = bitset![5, 9];
+        assert_eq!(bitset![3, 7], result.difference(&other).unwrap());
+        assert_eq!(bitset![5], result.intersected_with(&other).unwrap());
+        assert_eq!(bitset![3, 5, 7, 9], result.union(&other).unwrap());
+    }
+
+    #[test]
+    fn chained_transforms() {
+        let result = shrunken_result();
+
+        let expected = bitsetvec![{ 5 }, { 7 }];
+        let actual = result
+            .intersected_with(&bitset![5, 7])
+            .minimal_sets()
+            .filter_sets(|node_set| !node_set.is_empty());
+        assert_eq!(expected, actual.unwrap());
+    }
+}
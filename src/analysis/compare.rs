@@ -0,0 +1,136 @@
+use super::*;
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Changes in the roles of nodes between two analyses, as returned by `compare_analyses`. Nodes
+/// are identified by their public keys, so that analyses of different FBAS snapshots can be
+/// compared.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RoleDiff {
+    /// Nodes that are in the new top tier but weren't in the old one.
+    pub top_tier_entrants: Vec<PublicKey>,
+    /// Nodes that were in the old top tier but aren't in the new one.
+    pub top_tier_leavers: Vec<PublicKey>,
+    /// Nodes that form a minimal blocking set by themselves in the new analysis but didn't in the
+    /// old one.
+    pub new_single_points_of_failure: Vec<PublicKey>,
+    /// Nodes that formed a minimal blocking set by themselves in the old analysis but don't
+    /// anymore in the new one.
+    pub former_single_points_of_failure: Vec<PublicKey>,
+}
+impl RoleDiff {
+    /// `true` if no node changed its role.
+    pub fn is_empty(&self) -> bool {
+        self.top_tier_entrants.is_empty()
+            && self.top_tier_leavers.is_empty()
+            && self.new_single_points_of_failure.is_empty()
+            && self.former_single_points_of_failure.is_empty()
+    }
+}
+
+/// Compares the top tiers and single-node minimal blocking sets of two analyses. Nodes are
+/// matched by public key.
+pub fn compare_analyses(old: &Analysis, new: &Analysis) -> RoleDiff {
+    let (old_top_tier, new_top_tier) = (top_tier_public_keys(old), top_tier_public_keys(new));
+    let (old_spofs, new_spofs) = (
+        single_points_of_failure_public_keys(old),
+        single_points_of_failure_public_keys(new),
+    );
+    RoleDiff {
+        top_tier_entrants: new_top_tier.difference(&old_top_tier).cloned().collect(),
+        top_tier_leavers: old_top_tier.difference(&new_top_tier).cloned().collect(),
+        new_single_points_of_failure: new_spofs.difference(&old_spofs).cloned().collect(),
+        former_single_points_of_failure: old_spofs.difference(&new_spofs).cloned().collect(),
+    }
+}
+
+fn top_tier_public_keys(analysis: &Analysis) -> BTreeSet<PublicKey> {
+    analysis
+        .fbas()
+        .public_keys_of(&analysis.top_tier().unwrap())
+        .into_iter()
+        .collect()
+}
+
+fn single_points_of_failure_public_keys(analysis: &Analysis) -> BTreeSet<PublicKey> {
+    let singletons: Vec<NodeIdSet> = analysis
+        .minimal_blocking_sets()
+        .unwrap()
+        .into_iter()
+        .filter(|blocking_set| blocking_set.len() == 1)
+        .collect();
+    analysis
+        .fbas()
+        .public_keys_of(&involved_nodes(&singletons))
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_analyses_detects_role_changes() {
+        let old_fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 1, "validators": ["n0"] }
+            }
+        ]"#,
+        );
+        let new_fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n3"] }
+            },
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n3"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n3"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            }
+        ]"#,
+        );
+        let expected = RoleDiff {
+            top_tier_entrants: vec!["n3".to_string()],
+            top_tier_leavers: vec!["n2".to_string()],
+            new_single_points_of_failure: vec![
+                "n0".to_string(),
+                "n1".to_string(),
+                "n3".to_string(),
+            ],
+            former_single_points_of_failure: vec![],
+        };
+        let actual = compare_analyses(&Analysis::new(&old_fbas), &Analysis::new(&new_fbas));
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn compare_analyses_of_same_fbas_is_empty() {
+        let fbas = Fbas::from_json_file(std::path::Path::new("test_data/correct_trivial.json"));
+        let diff = compare_analyses(&Analysis::new(&fbas), &Analysis::new(&fbas));
+        assert!(diff.is_empty());
+    }
+}
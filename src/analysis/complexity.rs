@@ -0,0 +1,88 @@
+use super::*;
+
+use serde::Serialize;
+
+/// Cheap estimate of how expensive the standard analyses (minimal quorums etc.) will be for an
+/// FBAS, as returned by `Analysis::estimate_complexity`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComplexityEstimate {
+    /// Number of core nodes, i.e., nodes in quorum-containing strongly connected components.
+    pub core_nodes: usize,
+    /// Number of strongly connected components that contain quorums.
+    pub consensus_clusters: usize,
+    /// Number of consensus clusters that are symmetric clusters; for these, results can be
+    /// derived directly without any search.
+    pub symmetric_clusters: usize,
+    /// Rough upper bound for the number of search steps needed, as an order of magnitude (i.e.,
+    /// log10). Each node in a non-symmetric consensus cluster doubles the search space.
+    pub search_space_log10: f64,
+}
+impl ComplexityEstimate {
+    /// `true` if all consensus clusters are symmetric, i.e., the analyses will be (almost)
+    /// instant.
+    pub fn is_trivial(&self) -> bool {
+        self.consensus_clusters == self.symmetric_clusters
+    }
+}
+
+impl Analysis {
+    /// Estimate how expensive the standard analyses will be, without running them.
+    pub fn estimate_complexity(&self) -> ComplexityEstimate {
+        let fbas = self.fbas();
+        let standard_form_fbas = fbas.with_standard_form_quorum_sets();
+        let clusters: Vec<(usize, bool)> = find_sets(fbas, |consensus_clusters, _| {
+            consensus_clusters
+                .into_iter()
+                .map(|nodes| {
+                    let is_symmetric = is_symmetric_cluster(&nodes, &standard_form_fbas).is_some();
+                    (nodes.len(), is_symmetric)
+                })
+                .collect()
+        });
+        let largest_nonsymmetric_cluster = clusters
+            .iter()
+            .filter(|(_, is_symmetric)| !is_symmetric)
+            .map(|&(size, _)| size)
+            .max()
+            .unwrap_or(0);
+        ComplexityEstimate {
+            core_nodes: clusters.iter().map(|&(size, _)| size).sum(),
+            consensus_clusters: clusters.len(),
+            symmetric_clusters: clusters
+                .iter()
+                .filter(|(_, is_symmetric)| *is_symmetric)
+                .count(),
+            search_space_log10: largest_nonsymmetric_cluster as f64 * 2f64.log10(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn estimate_complexity_of_symmetric_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let expected = ComplexityEstimate {
+            core_nodes: 3,
+            consensus_clusters: 1,
+            symmetric_clusters: 1,
+            search_space_log10: 0.,
+        };
+        let actual = Analysis::new(&fbas).estimate_complexity();
+        assert_eq!(expected, actual);
+        assert!(actual.is_trivial());
+    }
+
+    #[test]
+    fn estimate_complexity_of_nonsymmetric_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let estimate = Analysis::new(&fbas).estimate_complexity();
+        assert_eq!(fbas.core_nodes().len(), estimate.core_nodes);
+        assert_eq!(1, estimate.consensus_clusters);
+        assert!(!estimate.is_trivial());
+        assert!(estimate.search_space_log10 > 0.);
+    }
+}
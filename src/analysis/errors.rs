@@ -0,0 +1,14 @@
+use std::fmt;
+
+/// Returned by the `try_*` methods of `Analysis` for results that are only meaningful if the FBAS
+/// enjoys quorum intersection (see `Analysis::has_quorum_intersection`). FBASs without any quorums
+/// count as lacking quorum intersection, too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoQuorumIntersection;
+
+impl fmt::Display for NoQuorumIntersection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The FBAS lacks quorum intersection.")
+    }
+}
+impl std::error::Error for NoQuorumIntersection {}
@@ -14,6 +14,10 @@ pub struct Analysis {
     mq_shrunken_cache: RefCell<Option<Vec<NodeIdSet>>>,
     mbs_shrunken_cache: RefCell<Option<Vec<NodeIdSet>>>,
     mss_shrunken_cache: RefCell<Option<Vec<NodeIdSet>>>,
+    symmetric_fast_path_cache: RefCell<Option<bool>>,
+    symmetric_nodes_cache: SymmetricNodesCache,
+    groupings: Option<Vec<Grouping>>,
+    warnings: RefCell<Vec<AnalysisWarning>>,
 }
 impl Analysis {
     /// Start a new `Analysis`
@@ -27,7 +31,8 @@ impl Analysis {
             "Shrank to an FBAS of size {}.",
             fbas_shrunken.number_of_nodes()
         );
-        Analysis {
+        let n_unsatisfiable = fbas.number_of_nodes() - fbas_shrunken.number_of_nodes();
+        let analysis = Analysis {
             fbas_original: fbas.clone(),
             fbas_shrunken: RefCell::new(fbas_shrunken),
             shrink_manager: RefCell::new(shrink_manager),
@@ -35,7 +40,34 @@ impl Analysis {
             mq_shrunken_cache: RefCell::new(None),
             mbs_shrunken_cache: RefCell::new(None),
             mss_shrunken_cache: RefCell::new(None),
+            symmetric_fast_path_cache: RefCell::new(None),
+            symmetric_nodes_cache: SymmetricNodesCache::default(),
+            groupings: None,
+            warnings: RefCell::new(vec![]),
+        };
+        if n_unsatisfiable > 0 {
+            analysis.record_warning(
+                AnalysisWarningKind::UnsatisfiableNodes,
+                format!(
+                    "The quorum sets of {} nodes are not satisfiable; these nodes are ignored.",
+                    n_unsatisfiable
+                ),
+            );
         }
+        analysis
+    }
+    /// Start a new `Analysis` that remembers `groupings`, so that the `*_merged` methods (e.g.,
+    /// `minimal_quorums_merged`) can return results merged by group without having to pass
+    /// `groupings` each time. Merging doesn't happen automatically: all other methods still
+    /// return unmerged results.
+    pub fn new_with_groupings(fbas: &Fbas, groupings: &Groupings) -> Self {
+        assert_eq!(
+            fbas, groupings.fbas,
+            "Groupings must have been created for the analyzed FBAS!"
+        );
+        let mut analysis = Self::new(fbas);
+        analysis.groupings = Some(groupings.groupings.clone());
+        analysis
     }
     /// Shrink the FBAS to its core nodes, i.e., to the union of all quorum-containing strongly
     /// connected components. Future splitting sets returned by this object will miss any splitting
@@ -56,6 +88,43 @@ impl Analysis {
         self.fbas_shrunken.replace(new_fbas_shrunken);
         self.shrink_manager.replace(new_shrink_manager);
     }
+    /// Keep unsatisfiable nodes in the analyzed FBAS, treating them as crashed (see
+    /// `Fbas::assume_crash_faulty`), instead of dropping them from the (shrunken) ID space.
+    ///
+    /// Dropping a node doesn't lower any thresholds, so dropped nodes already count as faulty
+    /// from the perspective of the nodes that trust them; minimal quorums and minimal blocking
+    /// sets are therefore the same in both modes. Minimal splitting sets, however, can now
+    /// contain unsatisfiable nodes: a misconfigured node that is still trusted by others can help
+    /// splitting them if it lies.
+    pub fn keep_unsatisfiable_as_faulty(&mut self) {
+        debug!("Re-adding unsatisfiable nodes as crash-faulty nodes...");
+        let unsatisfiable_nodes = self.fbas_original.unsatisfiable_nodes();
+        let mut fbas = self.fbas_original.clone();
+        fbas.assume_crash_faulty(&unsatisfiable_nodes);
+
+        let mut ids_to_keep: NodeIdSet = self
+            .shrink_manager
+            .borrow()
+            .unshrink_table()
+            .iter()
+            .copied()
+            .collect();
+        ids_to_keep.union_with(&unsatisfiable_nodes);
+        let (new_fbas_shrunken, new_shrink_manager) = Fbas::shrunken(&fbas, ids_to_keep);
+        debug!(
+            "Grew to an FBAS of size {} (from size {}).",
+            new_fbas_shrunken.number_of_nodes(),
+            self.fbas_shrunken.borrow().number_of_nodes(),
+        );
+        debug!("Fixing previously cached values...");
+        self.reshrink_cached_results(&new_shrink_manager);
+        self.fbas_shrunken.replace(new_fbas_shrunken);
+        self.shrink_manager.replace(new_shrink_manager);
+    }
+    /// The analyzed FBAS, as passed to `new` (i.e., not shrunken).
+    pub(crate) fn fbas(&self) -> &Fbas {
+        &self.fbas_original
+    }
     /// Nodes in the analyzed FBAS - not filtered by relevance.
     pub fn all_nodes(&self) -> NodeIdSetResult {
         self.make_unshrunken_set_result(self.fbas_original.all_nodes())
@@ -70,17 +139,108 @@ impl Analysis {
     pub fn unsatisfiable_nodes(&self) -> NodeIdSetResult {
         self.make_unshrunken_set_result(self.fbas_original.unsatisfiable_nodes())
     }
+    /// All nodes that end up unsatisfiable if the nodes in `initial_faulty` fail, including the
+    /// nodes in `initial_faulty` themselves. Failures cascade: nodes that lose all their quorum
+    /// slices fail too, which might in turn cost further nodes their quorum slices, and so on.
+    /// Nodes that are unsatisfiable to begin with are always included.
+    pub fn cascade_from(&self, initial_faulty: &NodeIdSet) -> NodeIdSet {
+        let mut remaining = self.fbas_original.all_nodes();
+        remaining.difference_with(initial_faulty);
+        let (_, mut failed) = find_satisfiable_nodes(&remaining, &self.fbas_original);
+        failed.union_with(initial_faulty);
+        failed
+    }
+    /// Like `cascade_from`, but with nodes identified by their public keys. Unknown public keys
+    /// are ignored.
+    pub fn cascade_from_pretty(&self, initial_faulty: &[PublicKey]) -> Vec<PublicKey> {
+        let initial_faulty = self.fbas_original.node_ids_of(initial_faulty);
+        self.fbas_original
+            .public_keys_of(&self.cascade_from(&initial_faulty))
+    }
+    /// For each distinct set of trusted nodes (the nodes contained in a quorum set), the number
+    /// of nodes whose quorum sets contain exactly that set. Sorted by count (highest first), then
+    /// by node set. Nodes with empty quorum sets are not counted.
+    pub fn delegation_concentration(&self) -> Vec<(NodeIdSet, usize)> {
+        let mut counts: BTreeMap<NodeIdSet, usize> = BTreeMap::new();
+        for node in self.fbas_original.nodes.iter() {
+            let trusted_nodes = node.quorum_set.contained_nodes();
+            if !trusted_nodes.is_empty() {
+                *counts.entry(trusted_nodes).or_insert(0) += 1;
+            }
+        }
+        let mut result: Vec<(NodeIdSet, usize)> = counts.into_iter().collect();
+        result.sort_by(|(_, x), (_, y)| y.cmp(x));
+        result
+    }
+    /// All nodes paired with their rank scores (as computed by `Fbas::rank_nodes`), sorted by
+    /// rank score (highest first). Nodes with equal scores are sorted by node ID.
+    pub fn nodes_by_rank(&self) -> Vec<(NodeId, RankScore)> {
+        let mut result: Vec<(NodeId, RankScore)> = self
+            .fbas_original
+            .rank_nodes()
+            .into_iter()
+            .enumerate()
+            .collect();
+        result.sort_by(|(_, x), (_, y)| y.total_cmp(x));
+        result
+    }
+    /// All nodes paired with a composite "influence" score, sorted by score (highest first; ties
+    /// by node ID). The score of a node is its rank score (as in `nodes_by_rank`) plus the
+    /// fraction of all nodes that trust it, directly or transitively. This is the same score that
+    /// the splitting sets search uses for deciding which nodes to look at first.
+    pub fn node_influence_scores(&self) -> Vec<(NodeId, RankScore)> {
+        let all_nodes: Vec<NodeId> = self.fbas_original.all_nodes().iter().collect();
+        let scores = influence_scores(&all_nodes, &self.fbas_original);
+        sort_by_score(all_nodes, &scores)
+            .into_iter()
+            .map(|node_id| (node_id, scores[node_id]))
+            .collect()
+    }
+    /// Like `nodes_by_rank`, but with nodes identified by their public keys.
+    pub fn nodes_by_rank_pretty(&self) -> Vec<(PublicKey, RankScore)> {
+        self.nodes_by_rank()
+            .into_iter()
+            .map(|(node_id, score)| (self.fbas_original.nodes[node_id].public_key.clone(), score))
+            .collect()
+    }
     /// Regular quorum intersection check via finding all minimal quorums (algorithm inspired by
-    /// [Lachowski 2019](https://arxiv.org/abs/1902.06493)).
+    /// [Lachowski 2019](https://arxiv.org/abs/1902.06493)). FBASs without any quorums (such as
+    /// the empty FBAS) are treated as *not* enjoying quorum intersection, as none of their nodes
+    /// can ever make progress. A single node that only trusts itself enjoys quorum intersection.
     pub fn has_quorum_intersection(&self) -> bool {
         self.has_quorum_intersection_from_shrunken()
     }
-    /// Quorum intersection check that works without enumerating all minimal quorums.
+    /// Quantitative safety margin: the size of the smallest intersection of any two quorums,
+    /// or `None` if the FBAS doesn't enjoy quorum intersection. As the nodes in the intersection
+    /// of two quorums together form a splitting set, the smallest minimal splitting set is
+    /// never larger than this margin. It can be smaller though, as nodes outside of the
+    /// intersection can also cause splits by lying to the nodes that depend on them.
+    pub fn quorum_intersection_margin(&self) -> Option<usize> {
+        if !self.has_quorum_intersection() {
+            return None;
+        }
+        let quorums = self.minimal_quorums_shrunken();
+        quorums
+            .iter()
+            .enumerate()
+            .flat_map(|(i, quorum)| {
+                quorums[i..]
+                    .iter()
+                    .map(move |other_quorum| quorum.intersection(other_quorum).count())
+            })
+            .min()
+    }
+    /// Quorum intersection check that works without enumerating all minimal quorums. Returns
+    /// `(false, None)` for FBASs without any quorums, consistent with `has_quorum_intersection`.
     pub fn has_quorum_intersection_via_alternative_check(
         &self,
     ) -> (bool, Option<NodeIdSetVecResult>) {
-        if let Some(quorums) = find_nonintersecting_quorums(&self.fbas_shrunken.borrow()) {
+        if !self.fbas_shrunken.borrow().has_any_quorum() {
+            self.record_no_quorum_intersection_warning();
+            (false, None)
+        } else if let Some(quorums) = find_nonintersecting_quorums(&self.fbas_shrunken.borrow()) {
             assert!(quorums[0].is_disjoint(&quorums[1]));
+            self.record_no_quorum_intersection_warning();
             (
                 false,
                 Some(self.make_shrunken_set_vec_result(quorums.to_vec())),
@@ -89,18 +249,233 @@ impl Analysis {
             (true, None)
         }
     }
+    /// Warnings collected so far, in the order in which they first occurred. Each kind of warning
+    /// is recorded at most once. Only analyses that have actually been run can contribute
+    /// warnings, e.g., `NoQuorumIntersection` is only recorded after a quorum intersection check.
+    pub fn warnings(&self) -> Vec<AnalysisWarning> {
+        self.warnings.borrow().clone()
+    }
+    /// Quorum intersection check on the FBAS obtained by merging each grouping into a single
+    /// node (see [`Fbas::merged_by_group`]), i.e., treating each grouping as a unit that is
+    /// either entirely part of a quorum or not at all. Node-level quorum intersection implies
+    /// quorum intersection on this level, but not vice versa.
+    pub fn has_quorum_intersection_merged_by_group(&self, groupings: &Groupings) -> bool {
+        assert_eq!(
+            &self.fbas_original, groupings.fbas,
+            "Groupings must have been created for the analyzed FBAS!"
+        );
+        let (merged_fbas, _) = self.fbas_original.merged_by_group(groupings);
+        Analysis::new(&merged_fbas).has_quorum_intersection()
+    }
     /// Minimal quorums - no proper subset of any of these node sets is a quorum.
     pub fn minimal_quorums(&self) -> NodeIdSetVecResult {
         self.make_shrunken_set_vec_result(self.minimal_quorums_shrunken())
     }
+    /// Like `minimal_quorums`, but the search stops as soon as more than `max` minimal quorums
+    /// have been found; the returned `bool` is `true` if that happened. A capped result is *not*
+    /// complete: it contains `max` minimal quorums (not necessarily the smallest ones) and most
+    /// metrics derived from it (number of quorums, top tier, ...) are only lower bounds. Uses the
+    /// cached minimal quorums if available; capped results aren't cached.
+    pub fn minimal_quorums_capped(&self, max: usize) -> (NodeIdSetVecResult, bool) {
+        let cached_quorums = self.mq_shrunken_cache.borrow().clone();
+        let (quorums, hit_cap) = if let Some(mut quorums) = cached_quorums {
+            let hit_cap = quorums.len() > max;
+            quorums.truncate(max);
+            (quorums, hit_cap)
+        } else {
+            find_minimal_quorums_capped(&self.fbas_shrunken.borrow(), max)
+        };
+        if hit_cap {
+            self.record_warning(
+                AnalysisWarningKind::SearchCapped,
+                format!("Stopped looking for minimal quorums after finding {}.", max),
+            );
+        }
+        (self.make_shrunken_set_vec_result(quorums), hit_cap)
+    }
+    /// Up to `n` distinct minimal quorums, found via randomized greedy searches seeded with `seed`
+    /// (same seed, same result). Meant for FBASs in which enumerating all minimal quorums is
+    /// infeasible. The sample is *not* uniform - minimal quorums that are "easier to hit" are
+    /// overrepresented - so any statistics derived from it (sizes, top tier, ...) are only
+    /// estimates. Results aren't cached. Requires the `quorum-sampling` feature.
+    #[cfg(feature = "quorum-sampling")]
+    pub fn sample_minimal_quorums(&self, n: usize, seed: u64) -> NodeIdSetVecResult {
+        use rand::{rngs::StdRng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.make_shrunken_set_vec_result(sample_minimal_quorums(
+            &self.fbas_shrunken.borrow(),
+            n,
+            &mut rng,
+        ))
+    }
+    /// Minimal quorums that consist only of nodes not in `excluded`, i.e., of the FBAS after
+    /// assuming `excluded` to be crash-faulty (see `Fbas::assume_crash_faulty`). Answers whether
+    /// the remaining nodes can still make progress if `excluded` is offline or censored; empty if
+    /// they can't. Not cached; each call runs a new analysis.
+    pub fn minimal_quorums_excluding(&self, excluded: &NodeIdSet) -> NodeIdSetVecResult {
+        Analysis::new(&self.fbas_original.with_crash_faulty(excluded)).minimal_quorums()
+    }
+    /// Like `minimal_quorums_excluding`, but with `excluded` referred to by public keys. Unknown
+    /// public keys are ignored.
+    pub fn minimal_quorums_excluding_pretty(&self, excluded: &[PublicKey]) -> NodeIdSetVecResult {
+        self.minimal_quorums_excluding(&self.fbas_original.node_ids_of(excluded))
+    }
+    /// Number of minimal quorums. Uses the cached minimal quorums if available; else, minimal
+    /// quorums of symmetric clusters are counted without enumerating them (and the count isn't
+    /// cached).
+    pub fn minimal_quorums_count(&self) -> usize {
+        if let Some(quorums) = self.mq_shrunken_cache.borrow().as_ref() {
+            quorums.len()
+        } else {
+            count_minimal_quorums(&self.fbas_shrunken.borrow())
+        }
+    }
     /// Minimal blocking sets - minimal indispensable sets for global liveness.
     pub fn minimal_blocking_sets(&self) -> NodeIdSetVecResult {
         self.make_shrunken_set_vec_result(self.minimal_blocking_sets_shrunken())
     }
+    /// Minimal blocking sets that consist of at most `max_size` nodes. Uses the cached minimal
+    /// blocking sets if available; else runs a bounded search (whose result isn't cached).
+    pub fn minimal_blocking_sets_up_to_size(&self, max_size: usize) -> NodeIdSetVecResult {
+        let cached_blocking_sets = self.mbs_shrunken_cache.borrow().clone();
+        let blocking_sets = if let Some(blocking_sets) = cached_blocking_sets {
+            blocking_sets
+                .into_iter()
+                .filter(|blocking_set| blocking_set.len() <= max_size)
+                .collect()
+        } else {
+            find_minimal_blocking_sets_up_to_size(&self.fbas_shrunken.borrow(), max_size)
+        };
+        self.make_shrunken_set_vec_result(blocking_sets)
+    }
+    /// Minimal sets of nodes whose failure according to `model` leaves the remaining nodes
+    /// without a quorum they can safely make progress in. With `FaultModel::Crash`, these are
+    /// the minimal blocking sets. Byzantine nodes can additionally lie, which effectively redacts
+    /// them from their trusters' quorum sets (possibly turning these into quorum expanders, as
+    /// in the splitting sets analysis). Besides blocking all quorums, they can then split the
+    /// remaining nodes into non-intersecting quorums, so that correct nodes can no longer rely on
+    /// what they agree on. With `FaultModel::Byzantine`, the result is therefore formed by the
+    /// minimal sets among all minimal blocking sets and minimal splitting sets. Each set found
+    /// for the crash model contains a set found for the Byzantine model, so that the latter sets
+    /// are never larger.
+    pub fn minimal_blocking_sets_with_model(&self, model: FaultModel) -> NodeIdSetVecResult {
+        match model {
+            FaultModel::Crash => self.minimal_blocking_sets(),
+            FaultModel::Byzantine => {
+                let mut sets = self.minimal_blocking_sets_shrunken();
+                sets.extend(self.minimal_splitting_sets_shrunken());
+                self.make_shrunken_set_vec_result(remove_non_minimal_node_sets(sets))
+            }
+        }
+    }
+    /// Minimal sets of groups (e.g., organizations) whose failure blocks global liveness, i.e.,
+    /// minimal blocking sets merged by `groupings` and then reduced to minimal sets.
+    pub fn minimal_blocking_organizations(&self, groupings: &Groupings) -> NodeIdSetVecResult {
+        self.minimal_blocking_sets()
+            .merged_by_group(groupings)
+            .minimal_sets()
+    }
+    /// Minimal sets of nodes whose failure leaves none of the nodes of the grouping named
+    /// `org_name` (e.g., an organization) in any quorum, i.e., minimal threats to the liveness of
+    /// that grouping in particular. Found as the minimal blocking sets of a copy of the FBAS in
+    /// which each quorum set additionally requires one of the grouping's nodes, so that its
+    /// quorums are exactly the quorums of the analyzed FBAS that contain such a node. Panics if
+    /// there is no grouping named `org_name`.
+    pub fn org_liveness_blocking_sets(
+        &self,
+        groupings: &Groupings,
+        org_name: &str,
+    ) -> NodeIdSetVecResult {
+        let org = groupings
+            .get_by_name(org_name)
+            .unwrap_or_else(|| panic!("Unknown grouping: {}", org_name));
+        // one inner quorum set per node, as nodes that are only ever referenced together in the
+        // same validator list would be treated as symmetric to each other
+        let org_quorum_set = QuorumSet {
+            threshold: 1,
+            validators: vec![],
+            inner_quorum_sets: org
+                .validators
+                .iter()
+                .map(|&node_id| QuorumSet {
+                    threshold: 1,
+                    validators: vec![node_id],
+                    inner_quorum_sets: vec![],
+                })
+                .collect(),
+        };
+        let mut fbas = self.fbas_original.clone();
+        for node in fbas.nodes.iter_mut() {
+            node.quorum_set = QuorumSet {
+                threshold: 2,
+                validators: vec![],
+                inner_quorum_sets: vec![node.quorum_set.clone(), org_quorum_set.clone()],
+            };
+        }
+        NodeIdSetVecResult::new(find_minimal_blocking_sets(&fbas), None)
+    }
+    /// Minimal quorums in which some group (e.g., organization) holds at least `fraction` of the
+    /// member nodes, i.e., quorums that are not "organization-diverse". With `fraction` set to
+    /// `1.0`, these are the quorums formed by a single group. Nodes that belong to no group don't
+    /// count towards any group.
+    pub fn concentrated_quorums(&self, groupings: &Groupings, fraction: f64) -> NodeIdSetVecResult {
+        let concentrated: Vec<NodeIdSet> = self
+            .minimal_quorums()
+            .unwrap()
+            .into_iter()
+            .filter(|quorum| {
+                let mut members_per_group: HashMap<&str, usize> = HashMap::new();
+                for node_id in quorum.iter() {
+                    if let Some(grouping) = groupings.get_by_member(node_id) {
+                        *members_per_group.entry(&grouping.name).or_insert(0) += 1;
+                    }
+                }
+                members_per_group
+                    .values()
+                    .any(|&members| members as f64 >= fraction * quorum.len() as f64)
+            })
+            .collect();
+        NodeIdSetVecResult::new(concentrated, None)
+    }
+    /// Nodes that are part of every minimal blocking set, i.e., nodes without whose failure global
+    /// liveness can't be blocked. For most healthy FBASs, this is empty.
+    pub fn universally_critical_nodes(&self) -> NodeIdSetResult {
+        self.make_shrunken_set_result(common_nodes(&self.minimal_blocking_sets_shrunken()))
+    }
+    /// All nodes in the FBAS that aren't part of any minimal blocking set, i.e., nodes whose
+    /// failure never contributes to a minimal threat to global liveness. Includes unsatisfiable
+    /// nodes.
+    pub fn non_critical_nodes(&self) -> NodeIdSetResult {
+        let mut non_critical_nodes = self.fbas_original.all_nodes();
+        non_critical_nodes.difference_with(&self.minimal_blocking_sets().involved_nodes());
+        self.make_unshrunken_set_result(non_critical_nodes)
+    }
     /// Minimal splitting sets - minimal indispensable sets for safety.
     pub fn minimal_splitting_sets(&self) -> NodeIdSetVecResult {
         self.make_shrunken_set_vec_result(self.minimal_splitting_sets_shrunken())
     }
+    /// Like `minimal_splitting_sets`, but fails if the FBAS lacks quorum intersection, in which
+    /// case the minimal splitting sets degenerate to the (meaningless) empty set.
+    pub fn try_minimal_splitting_sets(&self) -> Result<NodeIdSetVecResult, NoQuorumIntersection> {
+        self.require_quorum_intersection()?;
+        Ok(self.minimal_splitting_sets())
+    }
+    /// One minimal splitting set of the smallest possible size, or `None` if there are no
+    /// splitting sets. Uses the cached minimal splitting sets if available; else runs a search
+    /// with an increasing size bound that stops as soon as a splitting set is found (its result
+    /// isn't cached). If there are several smallest splitting sets, which one is returned is
+    /// unspecified.
+    pub fn smallest_splitting_set(&self) -> Option<NodeIdSetResult> {
+        let cached_splitting_sets = self.mss_shrunken_cache.borrow().clone();
+        let smallest_splitting_set = if let Some(splitting_sets) = cached_splitting_sets {
+            splitting_sets
+                .into_iter()
+                .min_by_key(|splitting_set| splitting_set.len())
+        } else {
+            find_smallest_splitting_set(&self.fbas_shrunken.borrow())
+        };
+        smallest_splitting_set.map(|splitting_set| self.make_shrunken_set_result(splitting_set))
+    }
     /// For each minimal splitting set, returns two or more quorums that it's splitting, i.e.,
     /// quorums that lack quorum intersection after the splitting sets are deleted from the FBAS.
     pub fn minimal_splitting_sets_with_affected_quorums(
@@ -115,22 +490,298 @@ impl Analysis {
             })
             .collect()
     }
+    /// For each node, the size of the smallest minimal splitting set that it is part of, or
+    /// `None` if it isn't part of any minimal splitting set.
+    pub fn min_splitting_set_size_per_node(&self) -> HashMap<NodeId, Option<usize>> {
+        let mut result: HashMap<NodeId, Option<usize>> = self
+            .fbas_original
+            .all_nodes()
+            .iter()
+            .map(|x| (x, None))
+            .collect();
+        for splitting_set in self.minimal_splitting_sets().unwrap() {
+            for node_id in splitting_set.iter() {
+                let size = result.get_mut(&node_id).unwrap();
+                *size = Some(size.map_or(splitting_set.len(), |s| s.min(splitting_set.len())));
+            }
+        }
+        result
+    }
+    /// Pairs of minimal quorums that intersect in exactly one node, together with that node. Each
+    /// such node can split the two quorums by behaving differently towards each of them.
+    pub fn singleton_intersection_quorum_pairs(
+        &self,
+    ) -> Vec<(NodeIdSetResult, NodeIdSetResult, NodeId)> {
+        let quorums = self.minimal_quorums_shrunken();
+        let mut result = vec![];
+        for (i, quorum1) in quorums.iter().enumerate() {
+            for quorum2 in quorums.iter().skip(i + 1) {
+                let mut intersection = quorum1.intersection(quorum2);
+                if let (Some(node_id), None) = (intersection.next(), intersection.next()) {
+                    result.push((
+                        self.make_shrunken_set_result(quorum1.clone()),
+                        self.make_shrunken_set_result(quorum2.clone()),
+                        self.shrink_manager.borrow().unshrink_table()[node_id],
+                    ));
+                }
+            }
+        }
+        result
+    }
     /// Top tier - the set of nodes exclusively relevant when determining minimal quorums and
-    /// minimal blocking sets.
+    /// minimal blocking sets. If the FBAS lacks quorum intersection, this is the union of the top
+    /// tiers of all consensus clusters (see `top_tier_per_cluster`). Empty if the FBAS has no
+    /// quorums.
     pub fn top_tier(&self) -> NodeIdSetResult {
         self.make_shrunken_set_result(self.top_tier_shrunken())
     }
+    /// Like `top_tier`, but fails if the FBAS lacks quorum intersection, in which case the top
+    /// tier mixes up the top tiers of unrelated (or overlapping but split) parts of the FBAS.
+    pub fn try_top_tier(&self) -> Result<NodeIdSetResult, NoQuorumIntersection> {
+        self.require_quorum_intersection()?;
+        Ok(self.top_tier())
+    }
+    /// The top tier of the analyzed FBAS after assuming the nodes in `faulty` to be crash-faulty
+    /// (see `Fbas::assume_crash_faulty`). Compare with `top_tier` to see how the failure of
+    /// `faulty` reshapes the top tier. Not cached; each call runs a new analysis.
+    pub fn top_tier_after_assuming_faulty(&self, faulty: &NodeIdSet) -> NodeIdSetResult {
+        Analysis::new(&self.fbas_original.with_crash_faulty(faulty)).top_tier()
+    }
+    /// Like `top_tier_after_assuming_faulty`, but with `faulty` referred to by public keys.
+    /// Unknown public keys are ignored.
+    pub fn top_tier_after_assuming_faulty_pretty(&self, faulty: &[PublicKey]) -> NodeIdSetResult {
+        self.top_tier_after_assuming_faulty(&self.fbas_original.node_ids_of(faulty))
+    }
+    /// One top tier per consensus cluster, i.e., per strongly connected component that contains
+    /// quorums. If the FBAS enjoys quorum intersection, there is only one consensus cluster and
+    /// the result is equivalent to `top_tier`.
+    ///
+    /// A consensus cluster can also lack quorum intersection on its own, as in `broken.json`. Such
+    /// a cluster is split further into "camps": maximal groups of minimal quorums that pairwise
+    /// intersect. Each camp contributes its own top tier. Camps of the same cluster can overlap,
+    /// i.e., a node can be part of several of the returned top tiers.
+    pub fn top_tier_per_cluster(&self) -> Vec<NodeIdSetResult> {
+        self.consensus_clusters_and_camps_shrunken()
+            .into_iter()
+            .flat_map(|(_, camps)| camps)
+            .map(|camp| self.make_shrunken_set_result(involved_nodes(&camp)))
+            .collect()
+    }
+    /// Pairs of (consensus cluster, minimal quorums of each of its camps) in shrunken IDs. A
+    /// cluster that enjoys quorum intersection has exactly one camp with all of its minimal
+    /// quorums; see `top_tier_per_cluster`.
+    fn consensus_clusters_and_camps_shrunken(&self) -> Vec<(NodeIdSet, Vec<Vec<NodeIdSet>>)> {
+        let quorums = self.minimal_quorums_shrunken();
+        let consensus_clusters = find_sets(&self.fbas_shrunken.borrow(), |clusters, _| clusters);
+        consensus_clusters
+            .into_iter()
+            .map(|cluster| {
+                let quorums_in_cluster: Vec<NodeIdSet> = quorums
+                    .iter()
+                    .filter(|quorum| quorum.is_subset(&cluster))
+                    .cloned()
+                    .collect();
+                let mut camps = maximal_intersecting_families(&quorums_in_cluster);
+                if camps.len() <= 1 {
+                    camps = vec![quorums_in_cluster];
+                }
+                (cluster, camps)
+            })
+            .collect()
+    }
+    /// For each node, the index of the consensus cluster it belongs to, in the order used by
+    /// `top_tier_per_cluster` and `per_cluster_report` (as long as these don't split clusters
+    /// into camps). `None` for nodes that aren't part of any consensus cluster, e.g., edge nodes
+    /// or unsatisfiable nodes.
+    pub fn cluster_membership(&self) -> HashMap<NodeId, Option<usize>> {
+        let mut membership: HashMap<NodeId, Option<usize>> = self
+            .fbas_original
+            .all_nodes()
+            .into_iter()
+            .map(|node_id| (node_id, None))
+            .collect();
+        let consensus_clusters = find_sets(&self.fbas_shrunken.borrow(), |clusters, _| clusters);
+        for (cluster_index, cluster) in consensus_clusters.into_iter().enumerate() {
+            for node_id in self.make_shrunken_set_result(cluster).unwrap().into_iter() {
+                membership.insert(node_id, Some(cluster_index));
+            }
+        }
+        membership
+    }
+    /// One `ClusterReport` per consensus cluster, i.e., per strongly connected component that
+    /// contains quorums. Mostly useful for FBASs that lack quorum intersection because they
+    /// consist of several consensus clusters, whose results otherwise get mixed up.
+    ///
+    /// Like in `top_tier_per_cluster`, a consensus cluster that lacks quorum intersection on its
+    /// own is split into camps, with one report per camp. A camp can't be analyzed as an induced
+    /// sub-FBAS, as its top tier can still contain non-intersecting quorums of other camps.
+    /// Instead, its report is derived from the camp's minimal quorums: its minimal blocking sets
+    /// are the minimal sets that intersect all of them, and its minimal splitting sets are the
+    /// minimal pairwise intersections of them. The latter are splitting sets for the camp, but
+    /// smaller splitting sets might exist. The `nodes` of a camp's report are its top tier.
+    pub fn per_cluster_report(&self) -> Vec<ClusterReport> {
+        self.consensus_clusters_and_camps_shrunken()
+            .into_iter()
+            .flat_map(|(cluster, camps)| {
+                if camps.len() > 1 {
+                    camps
+                        .into_iter()
+                        .map(|camp| self.camp_report(camp))
+                        .collect()
+                } else {
+                    vec![self.cluster_report(cluster)]
+                }
+            })
+            .collect()
+    }
+    fn cluster_report(&self, cluster_shrunken: NodeIdSet) -> ClusterReport {
+        let cluster = self.make_shrunken_set_result(cluster_shrunken).unwrap();
+        let (cluster_fbas, shrink_manager) = self.fbas_original.shrunken(cluster.clone());
+        let analysis = Analysis::new(&cluster_fbas);
+        let in_original_ids = |result: NodeIdSetVecResult| {
+            NodeIdSetVecResult::new(result.unwrap(), Some(&shrink_manager))
+        };
+        ClusterReport {
+            nodes: NodeIdSetResult::new(cluster, None),
+            top_tier: NodeIdSetResult::new(analysis.top_tier().unwrap(), Some(&shrink_manager)),
+            minimal_quorums: in_original_ids(analysis.minimal_quorums()),
+            minimal_blocking_sets: in_original_ids(analysis.minimal_blocking_sets()),
+            minimal_splitting_sets: in_original_ids(analysis.minimal_splitting_sets()),
+        }
+    }
+    fn camp_report(&self, camp_quorums_shrunken: Vec<NodeIdSet>) -> ClusterReport {
+        let top_tier = involved_nodes(&camp_quorums_shrunken);
+        ClusterReport {
+            nodes: self.make_shrunken_set_result(top_tier.clone()),
+            top_tier: self.make_shrunken_set_result(top_tier),
+            minimal_blocking_sets: self
+                .make_shrunken_set_vec_result(minimal_hitting_sets(&camp_quorums_shrunken)),
+            minimal_splitting_sets: self.make_shrunken_set_vec_result(
+                minimal_pairwise_intersections(&camp_quorums_shrunken),
+            ),
+            minimal_quorums: self.make_shrunken_set_vec_result(camp_quorums_shrunken),
+        }
+    }
     /// If the top tier is symmetric, i.e., each two top-tier nodes have the same quorum set,
     /// return the top tier's common quorum set. Else return `None`.
     pub fn symmetric_top_tier(&self) -> Option<QuorumSet> {
         find_symmetric_top_tier(&self.fbas_original)
     }
+    /// If the top tier is symmetric, its minimal quorums, derived directly from the top tier's
+    /// common quorum set (i.e., without searching the whole FBAS). Else `None`. If `Some`, this
+    /// equals `minimal_quorums`.
+    pub fn symmetric_top_tier_minimal_quorums(&self) -> Option<NodeIdSetVecResult> {
+        self.symmetric_top_tier().map(|quorum_set| {
+            NodeIdSetVecResult::new(quorum_set.to_minimal_quorums(&self.fbas_original), None)
+        })
+    }
+    /// Fraction of top tier nodes whose quorum set (in standard form) equals the most common
+    /// quorum set among top tier nodes. A score of `1.0` means that all top tier nodes share the
+    /// same quorum set. Returns `0.0` if the top tier is empty.
+    pub fn top_tier_symmetry_score(&self) -> f64 {
+        let top_tier = self.top_tier().unwrap();
+        let mut counts: HashMap<QuorumSet, usize> = HashMap::new();
+        for node_id in top_tier.iter() {
+            let quorum_set = self.fbas_original.nodes[node_id]
+                .quorum_set
+                .to_standard_form(node_id);
+            *counts.entry(quorum_set).or_insert(0) += 1;
+        }
+        let most_common_count = counts.values().copied().max().unwrap_or(0);
+        if top_tier.is_empty() {
+            0.
+        } else {
+            most_common_count as f64 / top_tier.len() as f64
+        }
+    }
+    /// Fraction of pairs of top tier nodes (i.e., of nodes that are part of some minimal quorum)
+    /// that are both part of at least one common minimal quorum. A score of `1.0` means that each
+    /// two top tier nodes share a minimal quorum. Returns `0.0` if there are fewer than two top
+    /// tier nodes.
+    pub fn pairwise_quorum_cohesion(&self) -> f64 {
+        let quorums = self.minimal_quorums_shrunken();
+        let top_tier = involved_nodes(&quorums);
+        let mut quorum_partners: HashMap<NodeId, NodeIdSet> = HashMap::new();
+        for quorum in quorums.iter() {
+            for node_id in quorum.iter() {
+                quorum_partners
+                    .entry(node_id)
+                    .or_default()
+                    .union_with(quorum);
+            }
+        }
+        let n = top_tier.len();
+        let cohesive_pairs: usize = quorum_partners
+            .values()
+            .map(|partners| partners.len() - 1)
+            .sum::<usize>()
+            / 2;
+        if n < 2 {
+            0.
+        } else {
+            cohesive_pairs as f64 / (n * (n - 1) / 2) as f64
+        }
+    }
+    /// Like `top_tier`, but merged by the groupings passed to `new_with_groupings`. Equivalent to
+    /// `top_tier` if the analysis was created without groupings.
+    pub fn top_tier_merged(&self) -> NodeIdSetResult {
+        self.merged_if_grouped(self.top_tier(), |result, groupings| {
+            result.merged_by_group(groupings)
+        })
+    }
+    /// Like `minimal_quorums`, but merged by the groupings passed to `new_with_groupings` (and
+    /// reduced to minimal sets). Equivalent to `minimal_quorums` if the analysis was created
+    /// without groupings.
+    pub fn minimal_quorums_merged(&self) -> NodeIdSetVecResult {
+        self.merged_minimal_if_grouped(self.minimal_quorums())
+    }
+    /// Like `minimal_blocking_sets`, but merged by the groupings passed to `new_with_groupings`
+    /// (and reduced to minimal sets). Equivalent to `minimal_blocking_sets` if the analysis was
+    /// created without groupings.
+    pub fn minimal_blocking_sets_merged(&self) -> NodeIdSetVecResult {
+        self.merged_minimal_if_grouped(self.minimal_blocking_sets())
+    }
+    /// Like `minimal_splitting_sets`, but merged by the groupings passed to `new_with_groupings`
+    /// (and reduced to minimal sets). Equivalent to `minimal_splitting_sets` if the analysis was
+    /// created without groupings.
+    pub fn minimal_splitting_sets_merged(&self) -> NodeIdSetVecResult {
+        self.merged_minimal_if_grouped(self.minimal_splitting_sets())
+    }
+    /// Whether the minimal quorums and/or minimal splitting sets computed so far could be
+    /// extracted from symmetric clusters, i.e., without an (exponential) exhaustive search.
+    /// `None` if neither has been computed yet.
+    pub fn used_symmetric_fast_path(&self) -> Option<bool> {
+        *self.symmetric_fast_path_cache.borrow()
+    }
     /// Symmetric clusters - sets of nodes in which each two nodes have the same quorum set.
     /// Here, each found symmetric cluster is represented by its common quorum set.
     pub fn symmetric_clusters(&self) -> Vec<QuorumSet> {
         find_symmetric_clusters(&self.fbas_original)
     }
 
+    fn require_quorum_intersection(&self) -> Result<(), NoQuorumIntersection> {
+        if self.has_quorum_intersection() {
+            Ok(())
+        } else {
+            Err(NoQuorumIntersection)
+        }
+    }
+    fn merged_if_grouped<R>(&self, result: R, merge: impl Fn(R, &Groupings) -> R) -> R {
+        if let Some(groupings) = self.groupings.as_ref() {
+            merge(
+                result,
+                &Groupings::new(groupings.clone(), &self.fbas_original),
+            )
+        } else {
+            result
+        }
+    }
+    fn merged_minimal_if_grouped(&self, result: NodeIdSetVecResult) -> NodeIdSetVecResult {
+        self.merged_if_grouped(result, |result, groupings| {
+            result.merged_by_group(groupings).minimal_sets()
+        })
+    }
+
     #[rustfmt::skip]
     fn reshrink_cached_results(&mut self, new_shrink_manager: &ShrinkManager) {
         let mq_shrunken_cache = self.mq_shrunken_cache.borrow().clone().map(|mq_shrunken| {
@@ -142,35 +793,68 @@ impl Analysis {
         self.mq_shrunken_cache.replace(mq_shrunken_cache);
         self.mbs_shrunken_cache.replace(mbs_shrunken_cache);
         self.mss_shrunken_cache.replace(None);
+        // symmetric nodes are found per shrunken FBAS and hence can't be reused
+        self.symmetric_nodes_cache = SymmetricNodesCache::default();
     }
     fn has_quorum_intersection_from_shrunken(&self) -> bool {
-        self.cached_computation(
+        let has_quorum_intersection = self.cached_computation(
             &self.hqi_cache,
             || {
                 let quorums = self.minimal_quorums_shrunken();
                 !quorums.is_empty() && all_intersect(&quorums)
             },
             "has quorum intersection",
-        )
+        );
+        if !has_quorum_intersection {
+            self.record_no_quorum_intersection_warning();
+        }
+        has_quorum_intersection
+    }
+    fn record_warning(&self, kind: AnalysisWarningKind, message: String) {
+        let mut warnings = self.warnings.borrow_mut();
+        if warnings.iter().all(|warning| warning.kind != kind) {
+            warnings.push(AnalysisWarning { kind, message });
+        }
+    }
+    fn record_no_quorum_intersection_warning(&self) {
+        let message = if self.fbas_shrunken.borrow().has_any_quorum() {
+            "Some quorums don't intersect; other results might not make much sense."
+        } else {
+            "There are no quorums at all; other results might not make much sense."
+        };
+        self.record_warning(
+            AnalysisWarningKind::NoQuorumIntersection,
+            String::from(message),
+        );
     }
     fn minimal_quorums_shrunken(&self) -> Vec<NodeIdSet> {
         self.cached_computation_from_fbas_shrunken(
             &self.mq_shrunken_cache,
-            find_minimal_quorums,
+            |fbas| {
+                self.recording_fast_path(find_minimal_quorums_reporting_fast_path(
+                    fbas,
+                    &self.symmetric_nodes_cache,
+                ))
+            },
             "minimal quorums",
         )
     }
     fn minimal_blocking_sets_shrunken(&self) -> Vec<NodeIdSet> {
         self.cached_computation_from_fbas_shrunken(
             &self.mbs_shrunken_cache,
-            find_minimal_blocking_sets,
+            |fbas| find_minimal_blocking_sets_with_cache(fbas, &self.symmetric_nodes_cache),
             "minimal blocking sets",
         )
     }
     fn minimal_splitting_sets_shrunken(&self) -> Vec<NodeIdSet> {
         self.cached_computation_from_fbas_shrunken(
             &self.mss_shrunken_cache,
-            find_minimal_splitting_sets,
+            |fbas| {
+                self.recording_fast_path(find_minimal_splitting_sets_reporting_fast_path(
+                    fbas,
+                    &self.symmetric_nodes_cache,
+                ))
+            },
             "minimal splitting sets",
         )
     }
@@ -198,6 +882,15 @@ impl Analysis {
         }
     }
 
+    fn recording_fast_path(
+        &self,
+        (result, used_fast_path): (Vec<NodeIdSet>, bool),
+    ) -> Vec<NodeIdSet> {
+        let previous = self.symmetric_fast_path_cache.borrow().unwrap_or(true);
+        self.symmetric_fast_path_cache
+            .replace(Some(previous && used_fast_path));
+        result
+    }
     fn cached_computation_from_fbas_shrunken<R, F>(
         &self,
         cache: &RefCell<Option<R>>,
@@ -60,11 +60,95 @@ impl<'fbas> Groupings<'fbas> {
             .map(|q| self.merge_quorum_set(q))
             .collect()
     }
+    /// Merges the FBAS these groupings refer to into an FBAS with one node per grouping; see
+    /// `Fbas::merged_by_group` for how disagreeing members are resolved. Node IDs of the returned
+    /// FBAS are compacted; use `Fbas::merged_by_group` directly if you need to map them back.
+    pub fn merge_fbas(&self) -> Fbas {
+        self.fbas.merged_by_group(self).0
+    }
+    /// The same groupings, but referring to `merged_fbas`, as returned by `merge_fbas`: each
+    /// grouping only contains the node that represents it there. Useful for analyzing a merged
+    /// FBAS while still reporting results by grouping name. Groupings without any nodes are
+    /// dropped.
+    pub fn for_merged_fbas<'merged>(&self, merged_fbas: &'merged Fbas) -> Groupings<'merged> {
+        let groupings = self
+            .groupings
+            .iter()
+            .filter_map(|grouping| {
+                let representative = grouping.validators.first()?;
+                let merged_id =
+                    merged_fbas.get_node_id(&self.fbas.nodes[*representative].public_key)?;
+                Some(Grouping {
+                    name: grouping.name.clone(),
+                    validators: vec![merged_id],
+                })
+            })
+            .collect();
+        Groupings::new(groupings, merged_fbas)
+    }
+}
+
+impl Fbas {
+    /// Builds a genuinely smaller FBAS in which each grouping is represented by a single node.
+    /// The quorum set of such a node is the (merged) quorum set of its members; if members
+    /// disagree, it requires all of their merged quorum sets to be satisfied. Nodes that belong
+    /// to no grouping are kept as they are.
+    ///
+    /// Node IDs in the returned FBAS are shrunken IDs; the returned `ShrinkManager` maps them
+    /// back to the IDs that `Groupings::merge_node` assigns in `self`. Hence, unshrinking the
+    /// results of an analysis of the merged FBAS yields the same ID space as analysing `self`
+    /// and calling `merged_by_group` on the results afterwards. Merged nodes keep the public key
+    /// of their group's first validator.
+    pub fn merged_by_group(&self, groupings: &Groupings) -> (Fbas, ShrinkManager) {
+        let mut member_quorum_sets: HashMap<NodeId, Vec<QuorumSet>> = HashMap::new();
+        for node_id in self.all_nodes().iter() {
+            let merged_quorum_set =
+                groupings.merge_quorum_set(self.nodes[node_id].quorum_set.clone());
+            let quorum_sets = member_quorum_sets
+                .entry(groupings.merge_node(node_id))
+                .or_default();
+            if !quorum_sets.contains(&merged_quorum_set) {
+                quorum_sets.push(merged_quorum_set);
+            }
+        }
+        let mut merged_fbas = self.clone();
+        for (&representative, quorum_sets) in member_quorum_sets.iter_mut() {
+            merged_fbas.nodes[representative].quorum_set = if quorum_sets.len() == 1 {
+                quorum_sets.pop().unwrap()
+            } else {
+                quorum_sets.sort_unstable();
+                QuorumSet {
+                    threshold: quorum_sets.len(),
+                    validators: vec![],
+                    inner_quorum_sets: std::mem::take(quorum_sets),
+                }
+            };
+        }
+        merged_fbas.shrunken(member_quorum_sets.into_keys().collect())
+    }
+    /// Builds an FBAS in which nodes `a` and `b` are collapsed into a single node, as a special
+    /// case of `merged_by_group` with only one group. The merged node keeps the public key of `a`
+    /// and is mapped back to `a` by the returned `ShrinkManager`.
+    ///
+    /// Thresholds are left unchanged. If both nodes appear in the same validator list, the merged
+    /// node appears in it twice, i.e., it counts for both of them; if the list consists only of
+    /// `a` and `b`, it is collapsed into a single validator with a threshold of 1.
+    pub fn with_merged_nodes(&self, a: NodeId, b: NodeId) -> (Fbas, ShrinkManager) {
+        let groupings = Groupings::new(
+            vec![Grouping {
+                name: format!("{}+{}", self.nodes[a].public_key, self.nodes[b].public_key),
+                validators: vec![a, b],
+            }],
+            self,
+        );
+        self.merged_by_group(&groupings)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::Path;
 
     #[test]
     fn merge_node_sets_by_organization() {
@@ -124,4 +208,131 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn merged_by_group_yields_one_node_per_organization() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json")).to_core();
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[
+            {
+                "name": "Stellar Development Foundation",
+                "validators": [
+                    "GCM6QMP3DLRPTAZW2UZPCPX2LF3SXWXKPMP3GKFZBDSF3QZGV2G5QSTK",
+                    "GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH",
+                    "GABMKJM6I25XI4K7U6XWMULOUQIQ27BCTMLS6BYYSOWKTBUXVRJSXHYQ"
+                ]
+            }]"#,
+            &fbas,
+        );
+        let (merged_fbas, shrink_manager) = fbas.merged_by_group(&organizations);
+
+        assert_eq!(fbas.number_of_nodes() - 2, merged_fbas.number_of_nodes());
+        assert_eq!(
+            organizations.merge_node_set(fbas.all_nodes()),
+            shrink_manager.unshrink_set(&merged_fbas.all_nodes())
+        );
+    }
+
+    #[test]
+    fn merged_first_and_merged_after_agree_for_2019_stellar_network() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let organizations = Groupings::organizations_from_json_file(
+            Path::new("test_data/stellarbeat_organizations_2019-09-17.json"),
+            &fbas,
+        );
+        let analysis = Analysis::new(&fbas);
+        let (merged_fbas, shrink_manager) = fbas.merged_by_group(&organizations);
+        let merged_analysis = Analysis::new(&merged_fbas);
+
+        let merged_after_quorums = analysis
+            .minimal_quorums()
+            .merged_by_group(&organizations)
+            .minimal_sets()
+            .unwrap();
+        let merged_first_quorums =
+            shrink_manager.unshrink_sets(&merged_analysis.minimal_quorums().unwrap());
+        assert_eq!(merged_after_quorums.len(), merged_first_quorums.len());
+
+        let merged_after_blocking_sets = analysis
+            .minimal_blocking_sets()
+            .merged_by_group(&organizations)
+            .minimal_sets()
+            .unwrap();
+        let merged_first_blocking_sets =
+            shrink_manager.unshrink_sets(&merged_analysis.minimal_blocking_sets().unwrap());
+        assert_eq!(
+            merged_after_blocking_sets.len(),
+            merged_first_blocking_sets.len()
+        );
+    }
+
+    #[test]
+    fn merge_fbas_yields_merged_fbas_with_same_quorum_intersection() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let organizations = Groupings::organizations_from_json_file(
+            Path::new("test_data/stellarbeat_organizations_2019-09-17.json"),
+            &fbas,
+        );
+        let merged_fbas = organizations.merge_fbas();
+
+        assert_eq!(fbas.merged_by_group(&organizations).0, merged_fbas);
+        assert_eq!(
+            Analysis::new(&fbas).has_quorum_intersection(),
+            Analysis::new(&merged_fbas).has_quorum_intersection()
+        );
+    }
+
+    #[test]
+    fn groupings_for_merged_fbas_refer_to_representatives() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let organizations = Groupings::organizations_from_json_file(
+            Path::new("test_data/stellarbeat_organizations_2019-09-17.json"),
+            &fbas,
+        );
+        let merged_fbas = organizations.merge_fbas();
+        let merged_organizations = organizations.for_merged_fbas(&merged_fbas);
+
+        let merged_top_tier = Analysis::new(&merged_fbas).top_tier();
+        let expected: Vec<String> = Analysis::new(&fbas)
+            .top_tier()
+            .merged_by_group(&organizations)
+            .into_pretty_vec(&fbas, Some(&organizations));
+        let actual: Vec<String> = merged_top_tier
+            .merged_by_group(&merged_organizations)
+            .into_pretty_vec(&merged_fbas, Some(&merged_organizations));
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn with_merged_nodes_counts_merged_node_twice() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            }
+        ]"#,
+        );
+        let (merged_fbas, shrink_manager) = fbas.with_merged_nodes(0, 1);
+        let merged_quorums = shrink_manager.unshrink_sets(&find_minimal_quorums(&merged_fbas));
+
+        assert_eq!(3, merged_fbas.number_of_nodes());
+        assert_eq!(bitsetvec![{ 0, 2 }, { 0, 3 }], merged_quorums);
+        for mut quorum in merged_quorums.into_iter() {
+            quorum.insert(1);
+            assert!(fbas.is_quorum(&quorum));
+        }
+    }
 }
@@ -1,7 +1,14 @@
 use super::*;
 
+mod combinators;
+mod compare;
+mod complexity;
+mod errors;
 mod front_end;
+mod report;
 mod results;
+mod structure;
+mod warnings;
 
 mod blocking_sets;
 mod quorums;
@@ -15,21 +22,31 @@ pub mod preprocessing;
 pub mod sets;
 pub mod timing;
 
+pub use assume_faulty::FaultModel;
+pub use compare::{compare_analyses, RoleDiff};
+pub use complexity::ComplexityEstimate;
+pub use errors::NoQuorumIntersection;
 pub use front_end::Analysis;
+pub use report::{AnalysisReport, ClusterReport, SetSizeDescription, SetsDescription};
 pub use results::{NodeIdSetResult, NodeIdSetVecResult};
+pub use structure::{ComponentSummary, NodeSetSummary, StructuralSummary};
+pub use warnings::{AnalysisWarning, AnalysisWarningKind};
 
-pub use blocking_sets::find_minimal_blocking_sets;
+pub use blocking_sets::{find_minimal_blocking_sets, find_minimal_blocking_sets_up_to_size};
 pub use quorums::{contains_quorum, find_minimal_quorums, find_nonintersecting_quorums};
-pub use splitting_sets::find_minimal_splitting_sets;
+pub use splitting_sets::{find_minimal_splitting_sets, find_smallest_splitting_set};
 pub use symmetric_clusters::{find_symmetric_clusters, find_symmetric_top_tier};
 
 pub use sets::{
-    all_intersect, involved_nodes, is_set_of_minimal_node_sets, remove_non_minimal_node_sets,
+    all_intersect, common_nodes, involved_nodes, is_set_of_minimal_node_sets,
+    remove_non_minimal_node_sets,
 };
 
+pub(crate) use blocking_sets::find_minimal_blocking_sets_with_cache;
 pub(crate) use preprocessing::*;
 pub(crate) use quorums::*;
 pub(crate) use sets::*;
+pub(crate) use splitting_sets::find_minimal_splitting_sets_reporting_fast_path;
 pub(crate) use symmetric_clusters::*;
 pub(crate) use symmetric_nodes::*;
 
@@ -133,6 +150,325 @@ mod tests {
         );
     }
 
+    #[test]
+    fn minimal_blocking_sets_up_to_size_with_and_without_cache() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert!(analysis.minimal_blocking_sets_up_to_size(1).is_empty());
+        let bounded = analysis.minimal_blocking_sets_up_to_size(2);
+        assert_eq!(analysis.minimal_blocking_sets(), bounded);
+        assert_eq!(bounded, analysis.minimal_blocking_sets_up_to_size(2));
+    }
+
+    #[test]
+    fn empty_fbas_has_no_quorum_intersection() {
+        let fbas = Fbas::new();
+        let analysis = Analysis::new(&fbas);
+
+        assert!(!analysis.has_quorum_intersection());
+        assert_eq!(
+            (false, None),
+            analysis.has_quorum_intersection_via_alternative_check()
+        );
+        assert!(analysis.top_tier().unwrap().is_empty());
+    }
+
+    #[test]
+    fn fbas_without_quorums_has_no_quorum_intersection() {
+        let fbas = Fbas::from_json_str(r#"[{ "publicKey": "n0" }]"#);
+        let analysis = Analysis::new(&fbas);
+
+        assert!(!analysis.has_quorum_intersection());
+        assert_eq!(
+            (false, None),
+            analysis.has_quorum_intersection_via_alternative_check()
+        );
+        assert!(analysis.top_tier().unwrap().is_empty());
+    }
+
+    #[test]
+    fn single_node_fbas_has_quorum_intersection() {
+        let fbas = Fbas::from_json_str(
+            r#"[{ "publicKey": "n0", "quorumSet": { "threshold": 1, "validators": ["n0"] } }]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        assert!(analysis.has_quorum_intersection());
+        assert_eq!(
+            (true, None),
+            analysis.has_quorum_intersection_via_alternative_check()
+        );
+        assert_eq!(bitset![0], analysis.top_tier().unwrap());
+        assert_eq!(
+            (1, 1, (1, 1, 1.), vec![0, 1]),
+            analysis.minimal_quorums().describe()
+        );
+        assert!(analysis.minimal_splitting_sets().is_empty());
+    }
+
+    #[test]
+    fn describe_empty_results() {
+        let fbas = Fbas::new();
+        let analysis = Analysis::new(&fbas);
+
+        let expected = (0, 0, (0, 0, 0.), vec![0]);
+        assert_eq!(expected, analysis.minimal_quorums().describe());
+        assert_eq!(expected, analysis.minimal_blocking_sets().describe());
+        assert_eq!(expected, analysis.minimal_splitting_sets().describe());
+    }
+
+    #[test]
+    fn singleton_intersection_quorum_pairs_in_correct() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let actual: Vec<(NodeIdSet, NodeIdSet, NodeId)> = analysis
+            .singleton_intersection_quorum_pairs()
+            .into_iter()
+            .map(|(quorum1, quorum2, node_id)| (quorum1.unwrap(), quorum2.unwrap(), node_id))
+            .collect();
+        let expected = vec![
+            (bitset![0, 1], bitset![0, 10], 0),
+            (bitset![0, 1], bitset![1, 10], 1),
+            (bitset![0, 10], bitset![1, 10], 10),
+        ];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn no_singleton_intersection_quorum_pairs_in_2019_stellar_network() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert!(analysis.singleton_intersection_quorum_pairs().is_empty());
+    }
+
+    #[test]
+    fn hub_of_star_is_universally_critical() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "hub",
+                "quorumSet": { "threshold": 1, "validators": ["hub"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["hub", "n1"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["hub", "n2"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["hub", "n3"] }
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(bitset![0], analysis.universally_critical_nodes().unwrap());
+    }
+
+    #[test]
+    fn symmetric_fbas_has_no_universally_critical_nodes() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert!(analysis.universally_critical_nodes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn top_tier_shrinks_after_assuming_top_tier_node_faulty() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+        assert_eq!(bitset![0, 1, 10], analysis.top_tier().unwrap());
+
+        let actual = analysis.top_tier_after_assuming_faulty(&bitset![0]);
+        assert_eq!(bitset![1, 10], actual.unwrap());
+
+        let actual = analysis.top_tier_after_assuming_faulty(&bitset![2]);
+        assert_eq!(analysis.top_tier(), actual);
+    }
+
+    #[test]
+    fn minimal_quorums_excluding_top_tier_nodes() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let actual = analysis.minimal_quorums_excluding(&bitset![0]);
+        assert_eq!(bitsetvec![{1, 10}], actual.unwrap());
+
+        let actual = analysis.minimal_quorums_excluding(&bitset![0, 1]);
+        assert!(actual.is_empty());
+
+        let actual = analysis.minimal_quorums_excluding(&bitset![2]);
+        assert_eq!(analysis.minimal_quorums().unwrap(), actual.unwrap());
+    }
+
+    #[test]
+    fn pretty_variants_equal_variants_by_id() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+        let mut faulty = fbas.public_keys_of(&bitset![0]);
+        faulty.push(String::from("unknown"));
+
+        assert_eq!(
+            analysis
+                .top_tier_after_assuming_faulty(&bitset![0])
+                .unwrap(),
+            analysis
+                .top_tier_after_assuming_faulty_pretty(&faulty)
+                .unwrap()
+        );
+        assert_eq!(
+            analysis.minimal_quorums_excluding(&bitset![0]).unwrap(),
+            analysis.minimal_quorums_excluding_pretty(&faulty).unwrap()
+        );
+        assert_eq!(
+            fbas.public_keys_of(&analysis.cascade_from(&bitset![0])),
+            analysis.cascade_from_pretty(&faulty)
+        );
+    }
+
+    #[test]
+    fn try_variants_fail_without_quorum_intersection() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(
+            Err(NoQuorumIntersection),
+            analysis.try_minimal_splitting_sets()
+        );
+        assert_eq!(Err(NoQuorumIntersection), analysis.try_top_tier());
+    }
+
+    #[test]
+    fn try_variants_succeed_with_quorum_intersection() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(
+            Ok(analysis.minimal_splitting_sets()),
+            analysis.try_minimal_splitting_sets()
+        );
+        assert_eq!(Ok(analysis.top_tier()), analysis.try_top_tier());
+    }
+
+    #[test]
+    fn non_top_tier_nodes_are_non_critical() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let mut expected = fbas.all_nodes();
+        expected.difference_with(&analysis.top_tier().unwrap());
+        let actual = analysis.non_critical_nodes().unwrap();
+
+        assert_eq!(fbas.number_of_nodes() - 3, actual.len());
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn minimal_quorums_capped_with_and_without_cache() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let (capped, hit_cap) = analysis.minimal_quorums_capped(2);
+        assert_eq!(2, capped.len());
+        assert!(hit_cap);
+
+        let (uncapped, hit_cap) = analysis.minimal_quorums_capped(3);
+        assert_eq!(analysis.minimal_quorums(), uncapped);
+        assert!(!hit_cap);
+
+        let (capped_from_cache, hit_cap) = analysis.minimal_quorums_capped(2);
+        assert_eq!(capped, capped_from_cache);
+        assert!(hit_cap);
+    }
+
+    #[test]
+    #[cfg(feature = "quorum-sampling")]
+    fn sampled_minimal_quorums_are_minimal_quorums() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let sampled = analysis.sample_minimal_quorums(5, 42);
+        assert_eq!(5, sampled.len());
+        assert!(sampled.is_minimal());
+        for quorum in sampled.clone().unwrap() {
+            assert!(fbas.is_quorum(&quorum));
+            for node_id in quorum.iter() {
+                let mut smaller = quorum.clone();
+                smaller.remove(node_id);
+                assert!(!contains_quorum(&smaller, &fbas));
+            }
+        }
+        assert_eq!(sampled, analysis.sample_minimal_quorums(5, 42));
+    }
+
+    #[test]
+    #[cfg(feature = "quorum-sampling")]
+    fn sampling_minimal_quorums_finds_no_more_than_there_are() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let sampled = analysis.sample_minimal_quorums(10, 1).unwrap();
+        let all: HashSet<NodeIdSet> = analysis.minimal_quorums().unwrap().into_iter().collect();
+
+        assert!(sampled.len() <= all.len());
+        assert!(sampled.iter().all(|quorum| all.contains(quorum)));
+    }
+
+    #[test]
+    fn symmetric_fast_path_used_for_2019_stellar_network() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(None, analysis.used_symmetric_fast_path());
+        analysis.minimal_quorums();
+        assert_eq!(Some(true), analysis.used_symmetric_fast_path());
+    }
+
+    #[test]
+    fn symmetric_fast_path_used_for_splitting_sets_of_symmetric_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let analysis = Analysis::new(&fbas);
+
+        analysis.minimal_splitting_sets();
+        assert_eq!(Some(true), analysis.used_symmetric_fast_path());
+    }
+
+    #[test]
+    fn symmetric_fast_path_not_used_for_nonsymmetric_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        analysis.minimal_quorums();
+        assert_eq!(Some(false), analysis.used_symmetric_fast_path());
+    }
+
+    #[test]
+    fn symmetric_nodes_are_found_once_for_quorums_and_blocking_sets() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+        let symmetric_nodes_calls = || FIND_SYMMETRIC_NODES_CALLS.with(|calls| calls.get());
+
+        let calls_before = symmetric_nodes_calls();
+        let minimal_quorums = analysis.minimal_quorums();
+        let calls_after_mq = symmetric_nodes_calls();
+        let minimal_blocking_sets = analysis.minimal_blocking_sets();
+        let calls_after_mbs = symmetric_nodes_calls();
+
+        assert_eq!(1, calls_after_mq - calls_before);
+        assert_eq!(calls_after_mq, calls_after_mbs);
+        assert_eq!(find_minimal_quorums(&fbas), minimal_quorums.unwrap());
+        assert_eq!(
+            find_minimal_blocking_sets(&fbas),
+            minimal_blocking_sets.unwrap()
+        );
+    }
+
     #[test]
     fn analysis_nontrivial_shrink_to_core_nodes() {
         let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
@@ -153,6 +489,144 @@ mod tests {
         );
     }
 
+    #[test]
+    fn keeping_unsatisfiable_nodes_as_faulty_only_changes_splitting_sets() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n3", "unknown"] }
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+        let mut analysis_keeping_faulty = Analysis::new(&fbas);
+        analysis_keeping_faulty.keep_unsatisfiable_as_faulty();
+
+        assert_eq!(
+            bitset![3],
+            analysis_keeping_faulty.unsatisfiable_nodes().unwrap()
+        );
+        assert_eq!(
+            analysis.minimal_quorums().unwrap(),
+            analysis_keeping_faulty.minimal_quorums().unwrap()
+        );
+        assert_eq!(
+            analysis.minimal_blocking_sets().unwrap(),
+            analysis_keeping_faulty.minimal_blocking_sets().unwrap()
+        );
+        assert!(analysis.minimal_splitting_sets().is_empty());
+        assert_eq!(
+            bitsetvec![{ 0, 3 }, { 1, 3 }, { 2, 3 }],
+            analysis_keeping_faulty.minimal_splitting_sets().unwrap()
+        );
+    }
+
+    #[test]
+    fn quorum_intersection_margin_bounds_splitting_set_sizes() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json")).to_core();
+        let analysis = Analysis::new(&fbas);
+        let smallest_splitting_set_size = analysis
+            .minimal_splitting_sets()
+            .unwrap()
+            .iter()
+            .map(|splitting_set| splitting_set.len())
+            .min();
+        assert_eq!(Some(1), analysis.quorum_intersection_margin());
+        assert_eq!(
+            smallest_splitting_set_size,
+            analysis.quorum_intersection_margin()
+        );
+    }
+
+    #[test]
+    fn quorum_intersection_margin_is_none_without_quorum_intersection() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let analysis = Analysis::new(&fbas);
+        assert_eq!(None, analysis.quorum_intersection_margin());
+    }
+
+    #[test]
+    fn smallest_splitting_set_with_and_without_cache() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json")).to_core();
+        let analysis = Analysis::new(&fbas);
+        let uncached = analysis.smallest_splitting_set().unwrap();
+        assert_eq!(1, uncached.len());
+
+        let splitting_sets = analysis.minimal_splitting_sets().unwrap();
+        let cached = analysis.smallest_splitting_set().unwrap();
+        assert!(splitting_sets.contains(&cached.unwrap()));
+        assert!(splitting_sets.contains(&uncached.unwrap()));
+    }
+
+    #[test]
+    fn analyzing_broken_fbas_records_no_quorum_intersection_warning() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let analysis = Analysis::new(&fbas);
+        assert!(!analysis
+            .warnings()
+            .iter()
+            .any(|warning| warning.kind == AnalysisWarningKind::NoQuorumIntersection));
+
+        assert!(!analysis.has_quorum_intersection());
+        assert!(!analysis.has_quorum_intersection());
+        let no_intersection_warnings: Vec<AnalysisWarning> = analysis
+            .warnings()
+            .into_iter()
+            .filter(|warning| warning.kind == AnalysisWarningKind::NoQuorumIntersection)
+            .collect();
+        assert_eq!(1, no_intersection_warnings.len());
+    }
+
+    #[test]
+    fn analyzing_correct_fbas_records_no_warnings_unless_capped() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let analysis = Analysis::new(&fbas);
+        assert!(analysis.has_quorum_intersection());
+        assert_eq!(Vec::<AnalysisWarning>::new(), analysis.warnings());
+
+        let analysis = Analysis::new(&fbas);
+        analysis.minimal_quorums_capped(1);
+        let kinds: Vec<AnalysisWarningKind> = analysis
+            .warnings()
+            .into_iter()
+            .map(|warning| warning.kind)
+            .collect();
+        assert_eq!(vec![AnalysisWarningKind::SearchCapped], kinds);
+    }
+
+    #[test]
+    fn min_splitting_set_size_per_node_for_correct_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json")).to_core();
+        let analysis = Analysis::new(&fbas);
+        let splitting_nodes = analysis.minimal_splitting_sets().involved_nodes();
+        assert_eq!(4, splitting_nodes.len());
+
+        let actual = analysis.min_splitting_set_size_per_node();
+
+        assert_eq!(fbas.number_of_nodes(), actual.len());
+        for node_id in fbas.all_nodes().iter() {
+            let expected = if splitting_nodes.contains(node_id) {
+                Some(1)
+            } else {
+                None
+            };
+            assert_eq!(expected, actual[&node_id]);
+        }
+    }
+
     #[test]
     fn splitting_sets_with_affected_quorums() {
         let fbas = Fbas::from_json_file(Path::new("test_data/correct.json")).to_core();
@@ -250,6 +724,580 @@ mod tests {
         );
     }
 
+    #[test]
+    fn top_tier_per_cluster_separates_consensus_clusters() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        let expected = vec![bitset![0, 1], bitset![2, 3]];
+        let mut actual: Vec<NodeIdSet> = analysis
+            .top_tier_per_cluster()
+            .into_iter()
+            .map(|top_tier| top_tier.unwrap())
+            .collect();
+        actual.sort();
+        assert_eq!(expected, actual);
+        assert_eq!(bitset![0, 1, 2, 3], analysis.top_tier().unwrap());
+    }
+
+    #[test]
+    fn top_tier_per_cluster_splits_broken_fbas_into_camps() {
+        // non-intersecting quorums within the same strongly connected component
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let expected = vec![bitset![3, 4, 10], bitset![3, 4, 6, 10]];
+        let actual: Vec<NodeIdSet> = analysis
+            .top_tier_per_cluster()
+            .into_iter()
+            .map(|top_tier| top_tier.unwrap())
+            .collect();
+        assert_eq!(expected, actual);
+        assert_eq!(bitset![3, 4, 6, 10], analysis.top_tier().unwrap());
+    }
+
+    #[test]
+    fn cluster_membership_of_broken_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let analysis = Analysis::new(&fbas);
+        let cluster = fbas.core_nodes();
+
+        let membership = analysis.cluster_membership();
+        assert_eq!(fbas.number_of_nodes(), membership.len());
+        for (node_id, cluster_index) in membership.into_iter() {
+            if cluster.contains(node_id) {
+                assert_eq!(Some(0), cluster_index);
+            } else {
+                assert_eq!(None, cluster_index);
+            }
+        }
+        assert!(cluster.len() < fbas.number_of_nodes());
+    }
+
+    #[test]
+    fn cluster_membership_separates_consensus_clusters() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 1, "validators": ["n0"] }
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        let membership = analysis.cluster_membership();
+        assert_eq!(membership[&0], membership[&1]);
+        assert_eq!(membership[&2], membership[&3]);
+        assert_ne!(membership[&0], membership[&2]);
+        assert!(membership[&0].is_some() && membership[&2].is_some());
+        assert_eq!(None, membership[&4]);
+    }
+
+    #[test]
+    fn quorum_intersection_merged_by_group_can_hold_where_node_level_fails() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "x",
+                "quorumSet": { "threshold": 2, "validators": ["x", "a1"] }
+            },
+            {
+                "publicKey": "a1",
+                "quorumSet": { "threshold": 2, "validators": ["a1", "x"] }
+            },
+            {
+                "publicKey": "y",
+                "quorumSet": { "threshold": 2, "validators": ["y", "a2"] }
+            },
+            {
+                "publicKey": "a2",
+                "quorumSet": { "threshold": 2, "validators": ["a2", "y"] }
+            }
+        ]"#,
+        );
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[{ "name": "A", "validators": ["a1", "a2"] }]"#,
+            &fbas,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        assert!(!analysis.has_quorum_intersection());
+        assert!(analysis.has_quorum_intersection_merged_by_group(&organizations));
+    }
+
+    #[test]
+    fn quorum_intersection_merged_by_group_of_2019_stellar_network() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let organizations = Groupings::organizations_from_json_file(
+            Path::new("test_data/stellarbeat_organizations_2019-09-17.json"),
+            &fbas,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        assert!(analysis.has_quorum_intersection_merged_by_group(&organizations));
+    }
+
+    #[test]
+    fn analysis_with_groupings_returns_merged_results_via_merged_variants() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json")).to_core();
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[
+            {
+                "name": "Stellar Development Foundation",
+                "validators": [
+                    "GCM6QMP3DLRPTAZW2UZPCPX2LF3SXWXKPMP3GKFZBDSF3QZGV2G5QSTK",
+                    "GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH",
+                    "GABMKJM6I25XI4K7U6XWMULOUQIQ27BCTMLS6BYYSOWKTBUXVRJSXHYQ"
+                ]
+            }]"#,
+            &fbas,
+        );
+        let analysis = Analysis::new_with_groupings(&fbas, &organizations);
+
+        assert_eq!(
+            analysis
+                .minimal_quorums()
+                .merged_by_group(&organizations)
+                .minimal_sets(),
+            analysis.minimal_quorums_merged()
+        );
+        assert_eq!(
+            analysis
+                .minimal_blocking_sets()
+                .merged_by_group(&organizations)
+                .minimal_sets(),
+            analysis.minimal_blocking_sets_merged()
+        );
+        assert_eq!(
+            analysis.top_tier().merged_by_group(&organizations),
+            analysis.top_tier_merged()
+        );
+        assert_ne!(
+            analysis.minimal_quorums(),
+            analysis.minimal_quorums_merged()
+        );
+    }
+
+    #[test]
+    fn analysis_without_groupings_returns_unmerged_results_via_merged_variants() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(
+            analysis.minimal_quorums(),
+            analysis.minimal_quorums_merged()
+        );
+        assert_eq!(analysis.top_tier(), analysis.top_tier_merged());
+    }
+
+    #[test]
+    fn top_tier_per_cluster_equals_top_tier_for_correct_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(vec![analysis.top_tier()], analysis.top_tier_per_cluster());
+    }
+
+    #[test]
+    fn minimal_blocking_organizations_for_2019_stellar_network() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let organizations = Groupings::organizations_from_json_file(
+            Path::new("test_data/stellarbeat_organizations_2019-09-17.json"),
+            &fbas,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        // any 2 of the 5 top tier organizations
+        let expected = (10, 5, (2, 2, 2.0), vec![0, 0, 10]);
+        let actual = analysis
+            .minimal_blocking_organizations(&organizations)
+            .describe();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn concentrated_quorums_respect_fraction() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "a1",
+                "quorumSet": { "threshold": 3, "validators": ["a1", "a2", "b1"] }
+            },
+            {
+                "publicKey": "a2",
+                "quorumSet": { "threshold": 3, "validators": ["a1", "a2", "b1"] }
+            },
+            {
+                "publicKey": "b1",
+                "quorumSet": { "threshold": 3, "validators": ["a1", "a2", "b1"] }
+            }
+        ]"#,
+        );
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[
+            { "name": "A", "validators": ["a1", "a2"] },
+            { "name": "B", "validators": ["b1"] }
+        ]"#,
+            &fbas,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(
+            bitsetvec![{0, 1, 2}],
+            analysis.concentrated_quorums(&organizations, 0.6).unwrap()
+        );
+        assert!(analysis
+            .concentrated_quorums(&organizations, 0.7)
+            .is_empty());
+    }
+
+    #[test]
+    fn org_liveness_blocking_sets_of_two_node_org() {
+        // a0 needs 2 of the top tier, a1 only needs t2; x is on its own
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "t0",
+                "quorumSet": { "threshold": 2, "validators": ["t0", "t1", "t2"] }
+            },
+            {
+                "publicKey": "t1",
+                "quorumSet": { "threshold": 2, "validators": ["t0", "t1", "t2"] }
+            },
+            {
+                "publicKey": "t2",
+                "quorumSet": { "threshold": 2, "validators": ["t0", "t1", "t2"] }
+            },
+            {
+                "publicKey": "a0",
+                "quorumSet": { "threshold": 2, "validators": ["t0", "t1", "t2"] }
+            },
+            {
+                "publicKey": "a1",
+                "quorumSet": { "threshold": 1, "validators": ["t2"] }
+            },
+            {
+                "publicKey": "x",
+                "quorumSet": { "threshold": 1, "validators": ["x"] }
+            }
+        ]"#,
+        );
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[
+            { "name": "A", "validators": ["a0", "a1"] }
+        ]"#,
+            &fbas,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        let expected = bitsetvec![{0, 1}, {0, 2}, {1, 2}, {2, 3}, {3, 4}];
+        let mut actual = analysis
+            .org_liveness_blocking_sets(&organizations, "A")
+            .unwrap();
+        actual.sort_unstable();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn failure_cascades_along_chain() {
+        // n1 needs n0, n2 needs n1, n3 needs n2; n4 only needs itself
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n0"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n1", "n2"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 1, "validators": ["n4"] }
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(bitset! {0, 1, 2, 3}, analysis.cascade_from(&bitset! {0}));
+        assert_eq!(bitset! {2, 3}, analysis.cascade_from(&bitset! {2}));
+        assert_eq!(bitset! {3, 4}, analysis.cascade_from(&bitset! {3, 4}));
+        assert_eq!(bitset! {}, analysis.cascade_from(&bitset! {}));
+    }
+
+    #[test]
+    fn byzantine_blocking_sets_include_splitting_sets() {
+        // every node is a quorum expander: quorums have 3 nodes but only overlap in 1
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3", "n4"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3", "n4"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3", "n4"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3", "n4"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3", "n4"] }
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        let crash = analysis.minimal_blocking_sets_with_model(FaultModel::Crash);
+        assert_eq!(
+            analysis.minimal_blocking_sets().unwrap(),
+            crash.clone().unwrap()
+        );
+        assert_eq!((10, 5, (3, 3, 3.0), vec![0, 0, 0, 10]), crash.describe());
+
+        let byzantine = analysis.minimal_blocking_sets_with_model(FaultModel::Byzantine);
+        let expected = bitsetvec![{ 0 }, { 1 }, { 2 }, { 3 }, { 4 }];
+        assert_eq!(expected, byzantine.unwrap());
+    }
+
+    #[test]
+    fn nodes_by_rank_sorts_by_descending_score() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 1, "validators": ["n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 1, "validators": ["n1", "n2"] }
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        let ranking = analysis.nodes_by_rank();
+        let ids: Vec<NodeId> = ranking.iter().map(|&(node_id, _)| node_id).collect();
+        assert_eq!(vec![2, 1, 0], ids);
+        assert!(ranking.windows(2).all(|w| w[0].1 >= w[1].1));
+
+        let pretty_ids: Vec<PublicKey> = analysis
+            .nodes_by_rank_pretty()
+            .into_iter()
+            .map(|(public_key, _)| public_key)
+            .collect();
+        assert_eq!(vec!["n2", "n1", "n0"], pretty_ids);
+    }
+
+    #[test]
+    fn top_tier_nodes_have_higher_influence_than_leaves() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+        let top_tier = analysis.top_tier().unwrap();
+        let leaves: NodeIdSet = fbas
+            .all_nodes()
+            .iter()
+            .filter(|&node_id| {
+                fbas.nodes
+                    .iter()
+                    .all(|node| !node.quorum_set.contained_nodes().contains(node_id))
+            })
+            .collect();
+        assert!(!leaves.is_empty());
+
+        let scores = analysis.node_influence_scores();
+        assert_eq!(fbas.number_of_nodes(), scores.len());
+        assert!(scores.windows(2).all(|w| w[0].1 >= w[1].1));
+
+        let min_top_tier_score = scores
+            .iter()
+            .filter(|(node_id, _)| top_tier.contains(*node_id))
+            .map(|&(_, score)| score)
+            .fold(f64::INFINITY, f64::min);
+        let max_leaf_score = scores
+            .iter()
+            .filter(|(node_id, _)| leaves.contains(*node_id))
+            .map(|&(_, score)| score)
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert!(min_top_tier_score > max_leaf_score);
+    }
+
+    #[test]
+    fn symmetric_top_tier_minimal_quorums_match_full_search_for_2019_stellar_network() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let mut expected = analysis.minimal_quorums().unwrap();
+        let mut actual = analysis
+            .symmetric_top_tier_minimal_quorums()
+            .unwrap()
+            .unwrap();
+        expected.sort_unstable();
+        actual.sort_unstable();
+
+        assert_eq!(expected.len(), actual.len());
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn minimal_quorums_count_with_and_without_cache() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let count_without_cache = analysis.minimal_quorums_count();
+        assert_eq!(count_without_cache, analysis.minimal_quorums().len());
+        assert_eq!(count_without_cache, analysis.minimal_quorums_count());
+    }
+
+    #[test]
+    fn top_tier_symmetry_score_of_symmetric_top_tier() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert!(analysis.symmetric_top_tier().is_some());
+        assert_eq!(1.0, analysis.top_tier_symmetry_score());
+    }
+
+    #[test]
+    fn top_tier_symmetry_score_of_asymmetric_top_tier() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n3", "n2", "n1", "n0"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2", "n3"] }
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(4, analysis.top_tier().len());
+        assert!(analysis.symmetric_top_tier().is_none());
+        assert!(analysis.symmetric_top_tier_minimal_quorums().is_none());
+        assert_eq!(0.75, analysis.top_tier_symmetry_score());
+    }
+
+    #[test]
+    fn pairwise_quorum_cohesion_of_symmetric_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(
+            bitsetvec![{ 0, 1 }, { 0, 2 }, { 1, 2 }],
+            analysis.minimal_quorums().unwrap()
+        );
+        assert_eq!(1.0, analysis.pairwise_quorum_cohesion());
+    }
+
+    #[test]
+    fn pairwise_quorum_cohesion_of_fragmented_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken_trivial.json"));
+        let analysis = Analysis::new(&fbas);
+
+        assert_eq!(
+            bitsetvec![{ 0 }, { 1, 2 }],
+            analysis.minimal_quorums().unwrap()
+        );
+        // only 1 and 2 share a quorum
+        assert_eq!(1. / 3., analysis.pairwise_quorum_cohesion());
+    }
+
+    #[test]
+    fn delegation_concentration_counts_identical_trusted_sets() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 1, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 1, "validators": ["n2"] }
+            },
+            {
+                "publicKey": "n4"
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+        let expected = vec![(bitset![0, 1], 3), (bitset![2], 1)];
+        let actual = analysis.delegation_concentration();
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     #[ignore]
     fn top_tier_analysis_big() {
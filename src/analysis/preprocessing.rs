@@ -1,6 +1,7 @@
 use super::*;
 
 extern crate pathfinding;
+use itertools::Itertools;
 use pathfinding::directed::strongly_connected_components::strongly_connected_components;
 
 pub type RankScore = f64;
@@ -12,6 +13,43 @@ impl Fbas {
     pub fn unsatisfiable_nodes(&self) -> NodeIdSet {
         find_satisfiable_nodes(&self.all_nodes(), self).1
     }
+    /// Returns `true` if the FBAS has at least one quorum. Cheap guard against degenerate inputs
+    /// (e.g., FBASs of only unsatisfiable nodes), for which most analyses return empty results.
+    pub fn has_any_quorum(&self) -> bool {
+        contains_quorum(&self.all_nodes(), self)
+    }
+    /// Returns the largest quorum contained in `node_set`, i.e., the union of all quorums that are
+    /// subsets of `node_set`, or `None` if `node_set` contains no quorum. Useful, e.g., for
+    /// checking whether some group of nodes can make progress on its own.
+    pub fn largest_quorum_in(&self, node_set: &NodeIdSet) -> Option<NodeIdSet> {
+        let (satisfiable, _) = find_satisfiable_nodes(node_set, self);
+        if satisfiable.is_empty() {
+            None
+        } else {
+            Some(satisfiable)
+        }
+    }
+    /// The largest `k` such that removing any `k` members of `quorum` still leaves a quorum in
+    /// what remains of `quorum`, i.e., how many of its members a (typically non-minimal) quorum
+    /// can lose. Minimal quorums have a redundancy of 0. Also returns 0 if `quorum` doesn't
+    /// contain a quorum in the first place. Exponential in the redundancy of `quorum`.
+    pub fn quorum_redundancy(&self, quorum: &NodeIdSet) -> usize {
+        if !contains_quorum(quorum, self) {
+            return 0;
+        }
+        (1..=quorum.len())
+            .find(|&k| {
+                quorum.iter().combinations(k).any(|removed| {
+                    let mut remaining = quorum.clone();
+                    for node_id in removed.into_iter() {
+                        remaining.remove(node_id);
+                    }
+                    !contains_quorum(&remaining, self)
+                })
+            })
+            .map(|k| k - 1)
+            .unwrap_or(0)
+    }
     pub fn strongly_connected_components(&self) -> Vec<NodeIdSet> {
         partition_into_strongly_connected_components(&self.all_nodes(), self)
     }
@@ -31,6 +69,60 @@ impl Fbas {
         }
         relevant_nodes
     }
+    /// Returns all nodes that no core node (see [`Fbas::core_nodes`]) trusts, directly or
+    /// transitively, i.e., nodes that are irrelevant to the core's consensus (like pure
+    /// observers).
+    pub fn nodes_unreachable_from_core(&self) -> NodeIdSet {
+        let reachable = self.reachable_closure_of_set(self.core_nodes());
+        let mut unreachable = self.all_nodes();
+        unreachable.difference_with(&reachable);
+        unreachable
+    }
+    /// Returns all nodes that `node_id` trusts, directly or transitively (including `node_id`
+    /// itself), i.e., all nodes whose configuration and behaviour the node's quorum decisions
+    /// could ever depend on. This is the reverse direction of `find_affected_nodes_per_node`.
+    pub fn reachable_closure(&self, node_id: NodeId) -> NodeIdSet {
+        self.reachable_closure_of_set(bitset![node_id])
+    }
+    /// `reachable_closure` for each node, indexed by node ID.
+    pub fn reachable_closures(&self) -> Vec<NodeIdSet> {
+        (0..self.nodes.len())
+            .map(|node_id| self.reachable_closure(node_id))
+            .collect()
+    }
+    fn reachable_closure_of_set(&self, mut reachable: NodeIdSet) -> NodeIdSet {
+        let mut to_visit: Vec<NodeId> = reachable.iter().collect();
+        while let Some(node_id) = to_visit.pop() {
+            for trusted_node_id in self.nodes[node_id].quorum_set.contained_nodes().iter() {
+                if reachable.insert(trusted_node_id) {
+                    to_visit.push(trusted_node_id);
+                }
+            }
+        }
+        reachable
+    }
+    /// Length of the shortest trust path from `from` to `to`, i.e., the minimal number of hops
+    /// along "is in the quorum set of" edges, or `None` if `from` doesn't trust `to`, not even
+    /// transitively. A node's distance to itself is 0. Also `None` if `from` or `to` isn't a node
+    /// of the FBAS.
+    pub fn trust_distance(&self, from: NodeId, to: NodeId) -> Option<usize> {
+        if from >= self.nodes.len() || to >= self.nodes.len() {
+            return None;
+        }
+        let mut visited = bitset![from];
+        let mut to_visit: VecDeque<(NodeId, usize)> = VecDeque::from(vec![(from, 0)]);
+        while let Some((node_id, distance)) = to_visit.pop_front() {
+            if node_id == to {
+                return Some(distance);
+            }
+            for trusted_node_id in self.nodes[node_id].quorum_set.contained_nodes().iter() {
+                if visited.insert(trusted_node_id) {
+                    to_visit.push_back((trusted_node_id, distance + 1));
+                }
+            }
+        }
+        None
+    }
     /// Returns all nodes v for which {v} is a quorum slice (and hence a quorum).
     /// These are often nodes that are somehow broken.
     pub fn one_node_quorums(&self) -> Vec<NodeId> {
@@ -75,6 +167,32 @@ impl Fbas {
         }
         self.shrunken(remaining_nodes).0
     }
+    /// Greedily removes nodes (via `without_nodes`) for as long as `predicate` still holds, e.g.,
+    /// for reducing an FBAS that triggers a surprising analysis result to a small example for a
+    /// bug report. Tries removing one node at a time and repeats until no single node can be
+    /// removed anymore. The result is locally minimal (removing any one of its nodes breaks
+    /// `predicate`) but not necessarily the smallest FBAS for which `predicate` holds. Changes
+    /// node IDs! Panics if `predicate` doesn't hold for `self`.
+    pub fn minimize_preserving(&self, predicate: impl Fn(&Fbas) -> bool) -> Self {
+        assert!(
+            predicate(self),
+            "The predicate needs to hold for the FBAS to be minimized."
+        );
+        let mut fbas = self.clone();
+        let mut removed_any = true;
+        while removed_any {
+            removed_any = false;
+            // going downwards, as removing a node only changes the IDs of nodes with higher IDs
+            for node_id in (0..fbas.number_of_nodes()).rev() {
+                let candidate = fbas.without_nodes(&[node_id]);
+                if predicate(&candidate) {
+                    fbas = candidate;
+                    removed_any = true;
+                }
+            }
+        }
+        fbas
+    }
 }
 
 /// Partitions `node_set` into the sets of `(satisfiable, unsatisfiable)` nodes.
@@ -118,16 +236,44 @@ pub fn partition_into_strongly_connected_components(
 /// `nodes` are ignored.
 // TODO dedup / harmonize this with Graph::get_rank_scores
 pub fn rank_nodes(nodes: &[NodeId], fbas: &Fbas) -> Vec<RankScore> {
+    rank_nodes_with_diagnostics(nodes, fbas).0
+}
+
+/// Convergence diagnostics for `rank_nodes`, see `rank_nodes_with_diagnostics`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankDiagnostics {
+    /// The first run after which no score changed by more than `RANK_CONVERGENCE_EPSILON`, or
+    /// `None` if scores didn't stabilize within `RANK_RUNS` runs (in which case the ranking
+    /// shouldn't be trusted too much).
+    pub converged_after_run: Option<usize>,
+    /// The largest change of any score during the last run.
+    pub final_max_delta: RankScore,
+}
+
+/// Number of runs performed by `rank_nodes`.
+pub const RANK_RUNS: usize = 100;
+/// Maximum change of any score during a run for `rank_nodes` to consider the scores converged.
+pub const RANK_CONVERGENCE_EPSILON: RankScore = 1e-6;
+
+/// Like `rank_nodes`, but additionally reports whether (and when) the scores stabilized. Always
+/// performs all `RANK_RUNS` runs, so that the returned scores equal those of `rank_nodes`.
+pub fn rank_nodes_with_diagnostics(
+    nodes: &[NodeId],
+    fbas: &Fbas,
+) -> (Vec<RankScore>, RankDiagnostics) {
     let nodes_set: NodeIdSet = nodes.iter().cloned().collect();
     assert_eq!(nodes.len(), nodes_set.len());
 
-    let runs = 100;
     let starting_score = 1. / nodes.len() as RankScore;
 
     let mut scores: Vec<RankScore> = vec![starting_score; fbas.nodes.len()];
     let mut last_scores: Vec<RankScore>;
+    let mut diagnostics = RankDiagnostics {
+        converged_after_run: None,
+        final_max_delta: 0.,
+    };
 
-    for _ in 0..runs {
+    for run in 1..=RANK_RUNS {
         last_scores = scores;
         scores = vec![0.; fbas.nodes.len()];
 
@@ -143,6 +289,16 @@ pub fn rank_nodes(nodes: &[NodeId], fbas: &Fbas) -> Vec<RankScore> {
                 scores[trusted_node_id] += last_scores[node_id] / l;
             }
         }
+        diagnostics.final_max_delta = scores
+            .iter()
+            .zip(last_scores.iter())
+            .map(|(&x, &y)| (x - y).abs())
+            .fold(0., RankScore::max);
+        if diagnostics.converged_after_run.is_none()
+            && diagnostics.final_max_delta < RANK_CONVERGENCE_EPSILON
+        {
+            diagnostics.converged_after_run = Some(run);
+        }
     }
     debug!(
         "Non-zero ranking scores: {:?}",
@@ -153,7 +309,8 @@ pub fn rank_nodes(nodes: &[NodeId], fbas: &Fbas) -> Vec<RankScore> {
             .filter(|&(_, s)| s > 0.)
             .collect::<Vec<(usize, RankScore)>>()
     );
-    scores
+    debug!("Ranking convergence: {:?}", diagnostics);
+    (scores, diagnostics)
 }
 
 /// Rank nodes and sort them by "highest rank score first". Nodes with equal score are sorted by
@@ -164,18 +321,38 @@ pub fn sort_by_rank(nodes: Vec<NodeId>, fbas: &Fbas) -> Vec<NodeId> {
     sort_by_score(nodes, &scores)
 }
 
+/// Rank scores (see `rank_nodes`) plus, for each node, the fraction of all nodes that it affects
+/// (see `find_affected_nodes_per_node`). Used for ordering candidates in searches: nodes with high
+/// scores are both trusted a lot and close to many nodes in the trust graph.
+pub(crate) fn influence_scores(nodes: &[NodeId], fbas: &Fbas) -> Vec<RankScore> {
+    debug!("Determining the set of affected nodes by each node...");
+    let affected_per_node = find_affected_nodes_per_node(fbas);
+    debug!("Done.");
+
+    debug!("Determining (page) rank scores");
+    let rank_scores = rank_nodes(nodes, fbas);
+    debug!("Done.");
+
+    rank_scores
+        .into_iter()
+        .enumerate()
+        .map(|(i, score)| score + affected_per_node[i].len() as f64 / fbas.number_of_nodes() as f64)
+        .collect()
+}
+
 /// Sort nodes by "highest score" first. Nodes with equal score are sorted by node ID (lowest ID
-/// first).
+/// first). NaN scores count as lower than any other score (and as equal to each other), so that
+/// the resulting order is total and deterministic even for degenerate rankings.
 pub fn sort_by_score(mut nodes: Vec<NodeId>, scores: &[RankScore]) -> Vec<NodeId> {
-    use std::cmp::Ordering::*;
-    nodes.sort_by(|x, y| match scores[*y].partial_cmp(&scores[*x]).unwrap() {
-        Less => Less,
-        Greater => Greater,
-        Equal => x.partial_cmp(y).unwrap(),
-    });
+    nodes.sort_by(|x, y| compare_scores(scores[*y], scores[*x]).then(x.cmp(y)));
     nodes
 }
 
+fn compare_scores(a: RankScore, b: RankScore) -> std::cmp::Ordering {
+    a.partial_cmp(&b)
+        .unwrap_or_else(|| b.is_nan().cmp(&a.is_nan()))
+}
+
 /// Find nodes that are affected by each node v, i.e., that point to v after a few steps; nodes
 /// also affect themselves.
 pub fn find_affected_nodes_per_node(fbas: &Fbas) -> Vec<NodeIdSet> {
@@ -235,6 +412,152 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn observer_is_unreachable_from_core() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n3"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "observer",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n3"
+            }
+        ]"#,
+        );
+        assert_eq!(bitset![0, 1], fbas.core_nodes());
+        assert_eq!(bitset![2], fbas.nodes_unreachable_from_core());
+    }
+
+    #[test]
+    fn trust_distances_in_chain() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 1, "validators": ["n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            }
+        ]"#,
+        );
+        assert_eq!(Some(0), fbas.trust_distance(0, 0));
+        assert_eq!(Some(1), fbas.trust_distance(0, 1));
+        assert_eq!(Some(2), fbas.trust_distance(0, 2));
+        assert_eq!(Some(3), fbas.trust_distance(0, 3));
+        assert_eq!(Some(1), fbas.trust_distance(3, 2));
+        assert_eq!(None, fbas.trust_distance(1, 0));
+        assert_eq!(None, fbas.trust_distance(3, 1));
+    }
+
+    #[test]
+    fn trust_distance_to_or_from_nonexistent_node_is_none() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        assert_eq!(Some(1), fbas.trust_distance(0, 1));
+        assert_eq!(None, fbas.trust_distance(0, 3));
+        assert_eq!(None, fbas.trust_distance(3, 0));
+        assert_eq!(None, fbas.trust_distance(3, 3));
+    }
+
+    #[test]
+    fn reachable_closures_in_chain() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 1, "validators": ["n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n2", "n3"] }
+            }
+        ]"#,
+        );
+        assert_eq!(bitset![0, 1, 2, 3], fbas.reachable_closure(0));
+        assert_eq!(bitset![1, 2, 3], fbas.reachable_closure(1));
+
+        let expected = bitsetvec![{0, 1, 2, 3}, {1, 2, 3}, {2, 3}, {2, 3}];
+        assert_eq!(expected, fbas.reachable_closures());
+    }
+
+    #[test]
+    fn largest_quorum_in_all_nodes_contains_core() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let largest_quorum = fbas.largest_quorum_in(&fbas.all_nodes()).unwrap();
+        assert!(fbas.is_quorum(&largest_quorum));
+        assert!(fbas.core_nodes().is_subset(&largest_quorum));
+        assert_eq!(fbas.satisfiable_nodes(), largest_quorum);
+    }
+
+    #[test]
+    fn no_largest_quorum_in_non_quorum() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        assert_eq!(None, fbas.largest_quorum_in(&bitset![0]));
+        assert_eq!(Some(bitset![0, 2]), fbas.largest_quorum_in(&bitset![0, 2]));
+    }
+
+    #[test]
+    fn quorum_redundancy_of_minimal_and_padded_quorums() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        assert_eq!(0, fbas.quorum_redundancy(&bitset![0, 2]));
+        assert_eq!(1, fbas.quorum_redundancy(&bitset![0, 1, 2]));
+        assert_eq!(0, fbas.quorum_redundancy(&bitset![0]));
+
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        for minimal_quorum in find_minimal_quorums(&fbas).iter() {
+            assert_eq!(0, fbas.quorum_redundancy(minimal_quorum));
+        }
+    }
+
+    #[test]
+    fn has_any_quorum_if_some_nodes_are_satisfiable() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        assert!(fbas.has_any_quorum());
+    }
+
+    #[test]
+    fn no_quorum_if_all_nodes_are_unsatisfiable() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1", "n3"] }
+            }
+        ]"#,
+        );
+        assert!(!fbas.has_any_quorum());
+    }
+
     #[test]
     fn one_node_quorums_are_relevant() {
         let fbas = Fbas::from_json_str(
@@ -561,4 +884,80 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn sort_by_score_sorts_nan_scores_last() {
+        let nodes = vec![0, 1, 2, 3, 4];
+        let scores = vec![f64::NAN, 0.5, f64::NAN, 0., 0.5];
+
+        let expected = vec![1, 4, 3, 0, 2];
+        let actual = sort_by_score(nodes, &scores);
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    #[cfg(feature = "qsc-simulation")]
+    fn ranking_of_tiered_full_mesh_converges_quickly() {
+        let graph = crate::simulation::Graph::new_tiered_full_mesh(&[3, 3, 3]);
+        let mut fbas = Fbas::new();
+        for outlinks in graph.outlinks.iter() {
+            fbas.add_generic_node(QuorumSet {
+                threshold: outlinks.len(),
+                validators: outlinks.clone(),
+                inner_quorum_sets: vec![],
+            });
+        }
+        let all_nodes: Vec<NodeId> = (0..fbas.number_of_nodes()).collect();
+
+        let (scores, diagnostics) = rank_nodes_with_diagnostics(&all_nodes, &fbas);
+
+        assert_eq!(rank_nodes(&all_nodes, &fbas), scores);
+        assert!(diagnostics.converged_after_run.unwrap() < RANK_RUNS / 2);
+        assert!(diagnostics.final_max_delta < RANK_CONVERGENCE_EPSILON);
+    }
+
+    #[test]
+    fn ranking_of_periodic_fbas_doesnt_converge() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 1, "validators": ["n0"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 1, "validators": ["n0"] }
+            }
+        ]"#,
+        );
+        let (_, diagnostics) = rank_nodes_with_diagnostics(&[0, 1, 2], &fbas);
+
+        assert_eq!(None, diagnostics.converged_after_run);
+        assert!(diagnostics.final_max_delta > RANK_CONVERGENCE_EPSILON);
+    }
+
+    #[test]
+    fn minimize_broken_fbas_preserving_lack_of_quorum_intersection() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let lacks_quorum_intersection =
+            |fbas: &Fbas| !Analysis::new(fbas).has_quorum_intersection();
+
+        let minimized = fbas.minimize_preserving(lacks_quorum_intersection);
+
+        assert!(lacks_quorum_intersection(&minimized));
+        assert!(minimized.number_of_nodes() < fbas.number_of_nodes());
+        for node_id in minimized.all_nodes().iter() {
+            assert!(!lacks_quorum_intersection(
+                &minimized.without_nodes(&[node_id])
+            ));
+        }
+        for node in minimized.nodes.iter() {
+            assert!(fbas.get_node_id(&node.public_key).is_some());
+        }
+    }
 }
@@ -1,12 +1,114 @@
 use super::*;
 
+use std::cell::Cell;
+
 /// Find all minimal quorums in the FBAS.
 pub fn find_minimal_quorums(fbas: &Fbas) -> Vec<NodeIdSet> {
+    find_minimal_quorums_reporting_fast_path(fbas, &SymmetricNodesCache::default()).0
+}
+
+/// Like `find_minimal_quorums`, but also returns `true` if all minimal quorums could be extracted
+/// from symmetric clusters, i.e., without an exhaustive search.
+pub(crate) fn find_minimal_quorums_reporting_fast_path(
+    fbas: &Fbas,
+    symmetric_nodes_cache: &SymmetricNodesCache,
+) -> (Vec<NodeIdSet>, bool) {
     info!("Starting to look for minimal quorums...");
-    let minimal_quorums = find_minimal_sets(fbas, minimal_quorums_finder);
+    let used_fast_path = Cell::new(true);
+    let minimal_quorums = find_minimal_sets(fbas, |clusters, fbas| {
+        minimal_quorums_finder(
+            clusters,
+            fbas,
+            &used_fast_path,
+            usize::MAX,
+            symmetric_nodes_cache,
+        )
+    });
     info!("Found {} minimal quorums.", minimal_quorums.len());
+    (minimal_quorums, used_fast_path.get())
+}
+
+/// Like `find_minimal_quorums`, but stops searching as soon as more than `max` minimal quorums
+/// have been found, returning only `max` of them. The returned `bool` is `true` if the search
+/// stopped early, i.e., if the result is incomplete.
+pub(crate) fn find_minimal_quorums_capped(fbas: &Fbas, max: usize) -> (Vec<NodeIdSet>, bool) {
+    info!(
+        "Starting to look for minimal quorums (stopping after {})...",
+        max
+    );
+    let mut minimal_quorums = find_minimal_sets(fbas, |clusters, fbas| {
+        minimal_quorums_finder(
+            clusters,
+            fbas,
+            &Cell::new(true),
+            max.saturating_add(1),
+            &SymmetricNodesCache::default(),
+        )
+    });
+    let hit_cap = minimal_quorums.len() > max;
+    if hit_cap {
+        warn!(
+            "Found more than {} minimal quorums; stopped searching.",
+            max
+        );
+        minimal_quorums.truncate(max);
+    } else {
+        info!("Found {} minimal quorums.", minimal_quorums.len());
+    }
+    (minimal_quorums, hit_cap)
+}
+
+/// Samples up to `n` distinct minimal quorums using randomized greedy searches: starting from all
+/// satisfiable nodes, nodes are removed in random order for as long as the remaining nodes still
+/// contain a quorum. Gives up after `MAX_SAMPLING_ATTEMPTS_PER_QUORUM * n` searches, so fewer than
+/// `n` minimal quorums are returned if the FBAS doesn't have that many (or they are rarely hit).
+#[cfg(feature = "quorum-sampling")]
+pub(crate) fn sample_minimal_quorums(
+    fbas: &Fbas,
+    n: usize,
+    rng: &mut impl rand::Rng,
+) -> Vec<NodeIdSet> {
+    use rand::seq::SliceRandom;
+    info!("Starting to sample up to {} minimal quorums...", n);
+    let satisfiable = fbas.satisfiable_nodes();
+    let mut sampled_quorums: BTreeSet<NodeIdSet> = BTreeSet::new();
+    if !satisfiable.is_empty() {
+        let mut candidates: Vec<NodeId> = satisfiable.iter().collect();
+        for _ in 0..n.saturating_mul(MAX_SAMPLING_ATTEMPTS_PER_QUORUM) {
+            if sampled_quorums.len() >= n {
+                break;
+            }
+            candidates.shuffle(rng);
+            let mut quorum = satisfiable.clone();
+            for &node_id in candidates.iter() {
+                if quorum.remove(node_id) {
+                    let (remaining_quorum, _) = find_satisfiable_nodes(&quorum, fbas);
+                    if remaining_quorum.is_empty() {
+                        quorum.insert(node_id);
+                    } else {
+                        quorum = remaining_quorum;
+                    }
+                }
+            }
+            sampled_quorums.insert(quorum);
+        }
+    }
+    let mut minimal_quorums: Vec<NodeIdSet> = sampled_quorums.into_iter().collect();
+    minimal_quorums.sort_by_key(|quorum| quorum.len());
+    info!("Sampled {} minimal quorums.", minimal_quorums.len());
     minimal_quorums
 }
+#[cfg(feature = "quorum-sampling")]
+const MAX_SAMPLING_ATTEMPTS_PER_QUORUM: usize = 10;
+
+/// Number of minimal quorums in the FBAS. Minimal quorums of symmetric clusters are counted
+/// combinatorially instead of being enumerated.
+pub(crate) fn count_minimal_quorums(fbas: &Fbas) -> usize {
+    info!("Starting to count minimal quorums...");
+    let count = find_sets(fbas, minimal_quorums_counter).into_iter().sum();
+    info!("Counted {} minimal quorums.", count);
+    count
+}
 
 /// Find at least two non-intersecting quorums. Use this function if you don't want to enumerate
 /// all minimal quorums and/or it is likely that the FBAS lacks quorum intersection and you want to
@@ -26,24 +128,62 @@ pub fn find_nonintersecting_quorums(fbas: &Fbas) -> Option<Vec<NodeIdSet>> {
     }
 }
 
-fn minimal_quorums_finder(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas) -> Vec<NodeIdSet> {
+fn minimal_quorums_counter(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas) -> Vec<usize> {
+    consensus_clusters
+        .into_iter()
+        .map(|nodes| {
+            if let Some(symmetric_cluster) =
+                is_symmetric_cluster(&nodes, &fbas.with_standard_form_quorum_sets())
+            {
+                symmetric_cluster.count_minimal_quorums(fbas)
+            } else {
+                minimal_quorums_finder(
+                    vec![nodes],
+                    fbas,
+                    &Cell::new(false),
+                    usize::MAX,
+                    &SymmetricNodesCache::default(),
+                )
+                .len()
+            }
+        })
+        .collect()
+}
+
+/// Stops after finding `max_quorums` minimal quorums.
+fn minimal_quorums_finder(
+    consensus_clusters: Vec<NodeIdSet>,
+    fbas: &Fbas,
+    used_fast_path: &Cell<bool>,
+    max_quorums: usize,
+    symmetric_nodes_cache: &SymmetricNodesCache,
+) -> Vec<NodeIdSet> {
     let mut found_quorums: Vec<NodeIdSet> = vec![];
 
     for (i, nodes) in consensus_clusters.into_iter().enumerate() {
+        let max_quorums_in_this_cluster = max_quorums - found_quorums.len();
+        if max_quorums_in_this_cluster == 0 {
+            debug!("Found enough minimal quorums; stopping.");
+            break;
+        }
         debug!("Finding minimal quorums in cluster {}...", i);
 
         if let Some(symmetric_cluster) =
             is_symmetric_cluster(&nodes, &fbas.with_standard_form_quorum_sets())
         {
             debug!("Cluster contains a symmetric quorum cluster! Extracting quorums...");
-            found_quorums.append(&mut symmetric_cluster.to_minimal_quorums(fbas));
+            found_quorums.append(
+                &mut symmetric_cluster.to_minimal_quorums_up_to(fbas, max_quorums_in_this_cluster),
+            );
         } else {
+            used_fast_path.set(false);
+
             debug!("Sorting nodes by rank...");
             let sorted_candidate_nodes = sort_by_rank(nodes.into_iter().collect(), fbas);
             debug!("Sorted.");
 
             debug!("Looking for symmetric nodes...");
-            let symmetric_nodes = find_symmetric_nodes_in_node_set(&nodes, fbas);
+            let symmetric_nodes = symmetric_nodes_cache.get_or_find(&nodes, fbas);
             debug!("Done.");
 
             let mut found_unexpanded_quorums_in_this_cluster = vec![];
@@ -54,9 +194,13 @@ fn minimal_quorums_finder(consensus_clusters: Vec<NodeIdSet>, fbas: &Fbas) -> Ve
                 &mut found_unexpanded_quorums_in_this_cluster,
                 &FbasValues::new(fbas, &symmetric_nodes),
                 true,
+                max_quorums_in_this_cluster,
+                &mut bitset![],
             );
-            found_quorums
-                .append(&mut symmetric_nodes.expand_sets(found_unexpanded_quorums_in_this_cluster))
+            let mut found_quorums_in_this_cluster =
+                symmetric_nodes.expand_sets(found_unexpanded_quorums_in_this_cluster);
+            found_quorums_in_this_cluster.truncate(max_quorums_in_this_cluster);
+            found_quorums.append(&mut found_quorums_in_this_cluster);
         }
     }
     found_quorums
@@ -66,9 +210,13 @@ fn minimal_quorums_finder_step(
     found_quorums: &mut Vec<NodeIdSet>,
     fbas_values: &FbasValues,
     selection_changed: bool,
+    max_quorums: usize,
+    buffer: &mut NodeIdSet,
 ) {
-    if selection_changed && fbas_values.fbas.is_quorum(&candidates.selection) {
-        if is_minimal_for_quorum(&candidates.selection, fbas_values.fbas) {
+    if found_quorums.len() >= max_quorums {
+        // return
+    } else if selection_changed && fbas_values.fbas.is_quorum(&candidates.selection) {
+        if is_minimal_for_quorum_using_buffer(&candidates.selection, fbas_values.fbas, buffer) {
             found_quorums.push(candidates.selection.clone());
             if found_quorums.len() % 100_000 == 0 {
                 debug!("...{} quorums found", found_quorums.len());
@@ -83,7 +231,14 @@ fn minimal_quorums_finder_step(
             .is_non_redundant_next(current_candidate, &candidates.selection)
         {
             candidates.selection.insert(current_candidate);
-            minimal_quorums_finder_step(candidates, found_quorums, fbas_values, true);
+            minimal_quorums_finder_step(
+                candidates,
+                found_quorums,
+                fbas_values,
+                true,
+                max_quorums,
+                buffer,
+            );
             candidates.selection.remove(current_candidate);
         }
         candidates.available.remove(current_candidate);
@@ -93,7 +248,14 @@ fn minimal_quorums_finder_step(
             &candidates.available,
             fbas_values.fbas,
         ) {
-            minimal_quorums_finder_step(candidates, found_quorums, fbas_values, false);
+            minimal_quorums_finder_step(
+                candidates,
+                found_quorums,
+                fbas_values,
+                false,
+                max_quorums,
+                buffer,
+            );
         }
         candidates.unprocessed.push_front(current_candidate);
         candidates.available.insert(current_candidate);
@@ -104,7 +266,10 @@ fn nonintersecting_quorums_finder(
     consensus_clusters: Vec<NodeIdSet>,
     fbas: &Fbas,
 ) -> Vec<NodeIdSet> {
-    if consensus_clusters.len() > 1 {
+    if consensus_clusters.is_empty() {
+        debug!("No consensus clusters - there are no quorums at all.");
+        vec![]
+    } else if consensus_clusters.len() > 1 {
         debug!("More than one consensus clusters - reducing to maximal quorums.");
         consensus_clusters
             .into_iter()
@@ -112,7 +277,7 @@ fn nonintersecting_quorums_finder(
             .collect()
     } else {
         warn!("There is only one consensus cluster - there might be no non-intersecting quorums and the subsequent search might be slow.");
-        let nodes = consensus_clusters.into_iter().next().unwrap_or_default();
+        let nodes = consensus_clusters.into_iter().next().unwrap();
         nonintersecting_quorums_finder_using_cluster(&nodes, fbas)
     }
 }
@@ -152,8 +317,7 @@ pub(crate) fn nonintersecting_quorums_finder_using_sorted_nodes(
         picks_left,
         true,
     ) {
-        assert!(intersecting_quorums.iter().all(|x| fbas.is_quorum(x)));
-        assert!(intersecting_quorums[0].is_disjoint(&intersecting_quorums[1]));
+        assert!(fbas.is_safety_violation(&intersecting_quorums[0], &intersecting_quorums[1]));
         intersecting_quorums.to_vec()
     } else {
         assert!(fbas.is_quorum(&candidates.available));
@@ -271,7 +435,7 @@ impl<'a> FbasValues<'a> {
 
 impl QuorumSet {
     /// Makes sense if the quorum set represents a symmetric quorum cluster...
-    fn to_minimal_quorums(&self, fbas: &Fbas) -> Vec<NodeIdSet> {
+    pub(crate) fn to_minimal_quorums(&self, fbas: &Fbas) -> Vec<NodeIdSet> {
         let quorums = self.to_quorum_slices();
         if self.contains_duplicates() {
             remove_non_minimal_x(quorums, is_minimal_for_quorum, fbas)
@@ -279,6 +443,27 @@ impl QuorumSet {
             quorums
         }
     }
+    /// Like `to_minimal_quorums`, but stops after `max` quorums (if quorums can be generated
+    /// lazily, i.e., if there are no duplicate validators).
+    fn to_minimal_quorums_up_to(&self, fbas: &Fbas, max: usize) -> Vec<NodeIdSet> {
+        if self.contains_duplicates() || self.threshold == 0 {
+            let mut quorums = self.to_minimal_quorums(fbas);
+            quorums.truncate(max);
+            quorums
+        } else {
+            self.nonempty_slices_iter(|qset| qset.threshold)
+                .take(max)
+                .collect()
+        }
+    }
+    /// Makes sense if the quorum set represents a symmetric quorum cluster...
+    fn count_minimal_quorums(&self, fbas: &Fbas) -> usize {
+        if self.contains_duplicates() {
+            self.to_minimal_quorums(fbas).len()
+        } else {
+            self.count_quorum_slices()
+        }
+    }
     /// Makes sense if the quorum set represents a symmetric quorum cluster...
     pub(crate) fn has_nonintersecting_quorums(&self) -> Option<(NodeIdSet, NodeIdSet)> {
         // make sure we aren't really a 1-node quorum
@@ -303,15 +488,26 @@ fn selection_satisfiable(selection: &NodeIdSet, available: &NodeIdSet, fbas: &Fb
 
 /// Returns `true` if any subset of `node_set` forms a quorum for `fbas`.
 pub fn contains_quorum(node_set: &NodeIdSet, fbas: &Fbas) -> bool {
-    let mut satisfiable = node_set.clone();
+    contains_quorum_using_buffer(node_set, fbas, &mut bitset![])
+}
 
-    while let Some(unsatisfiable_node) = satisfiable
+/// Like [`contains_quorum`], but uses (and overwrites) `buffer` instead of allocating a new set.
+/// Avoids allocations in hot loops.
+fn contains_quorum_using_buffer(node_set: &NodeIdSet, fbas: &Fbas, buffer: &mut NodeIdSet) -> bool {
+    buffer.clone_from(node_set);
+    shrink_to_contained_quorum(buffer, fbas)
+}
+
+/// Removes nodes from `node_set` until only its largest contained quorum (or nothing) is left;
+/// returns `true` if there is such a quorum.
+fn shrink_to_contained_quorum(node_set: &mut NodeIdSet, fbas: &Fbas) -> bool {
+    while let Some(unsatisfiable_node) = node_set
         .iter()
-        .find(|&x| !fbas.nodes[x].quorum_set.is_quorum_slice(&satisfiable))
+        .find(|&x| !fbas.nodes[x].quorum_set.is_quorum_slice(node_set))
     {
-        satisfiable.remove(unsatisfiable_node);
+        node_set.remove(unsatisfiable_node);
     }
-    !satisfiable.is_empty()
+    !node_set.is_empty()
 }
 
 pub(crate) fn complement_contains_quorum(node_set: &NodeIdSet, fbas: &Fbas) -> bool {
@@ -321,14 +517,22 @@ pub(crate) fn complement_contains_quorum(node_set: &NodeIdSet, fbas: &Fbas) -> b
 }
 
 fn is_minimal_for_quorum(quorum: &NodeIdSet, fbas: &Fbas) -> bool {
-    let mut tester = quorum.clone();
+    is_minimal_for_quorum_using_buffer(quorum, fbas, &mut bitset![])
+}
 
+/// Like [`is_minimal_for_quorum`], but uses (and overwrites) `buffer` instead of allocating new
+/// sets. Avoids allocations in hot loops.
+fn is_minimal_for_quorum_using_buffer(
+    quorum: &NodeIdSet,
+    fbas: &Fbas,
+    buffer: &mut NodeIdSet,
+) -> bool {
     for node_id in quorum.iter() {
-        tester.remove(node_id);
-        if contains_quorum(&tester, fbas) {
+        buffer.clone_from(quorum);
+        buffer.remove(node_id);
+        if shrink_to_contained_quorum(buffer, fbas) {
             return false;
         }
-        tester.insert(node_id);
     }
     true
 }
@@ -396,6 +600,102 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn count_minimal_quorums_of_symmetric_fbas_matches_enumeration() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0"], "innerQuorumSets": [
+                    { "threshold": 2, "validators": ["n1", "n2", "n3"] },
+                    { "threshold": 1, "validators": ["n4", "n5"] }
+                ]}
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0"], "innerQuorumSets": [
+                    { "threshold": 2, "validators": ["n1", "n2", "n3"] },
+                    { "threshold": 1, "validators": ["n4", "n5"] }
+                ]}
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0"], "innerQuorumSets": [
+                    { "threshold": 2, "validators": ["n1", "n2", "n3"] },
+                    { "threshold": 1, "validators": ["n4", "n5"] }
+                ]}
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n0"], "innerQuorumSets": [
+                    { "threshold": 2, "validators": ["n1", "n2", "n3"] },
+                    { "threshold": 1, "validators": ["n4", "n5"] }
+                ]}
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 2, "validators": ["n0"], "innerQuorumSets": [
+                    { "threshold": 2, "validators": ["n1", "n2", "n3"] },
+                    { "threshold": 1, "validators": ["n4", "n5"] }
+                ]}
+            },
+            {
+                "publicKey": "n5",
+                "quorumSet": { "threshold": 2, "validators": ["n0"], "innerQuorumSets": [
+                    { "threshold": 2, "validators": ["n1", "n2", "n3"] },
+                    { "threshold": 1, "validators": ["n4", "n5"] }
+                ]}
+            }
+        ]"#,
+        );
+        let fbas_2019 =
+            Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let correct_trivial = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+
+        assert_eq!(
+            find_minimal_quorums(&fbas).len(),
+            count_minimal_quorums(&fbas)
+        );
+        assert_eq!(
+            find_minimal_quorums(&fbas_2019).len(),
+            count_minimal_quorums(&fbas_2019)
+        );
+        assert_eq!(
+            find_minimal_quorums(&correct_trivial).len(),
+            count_minimal_quorums(&correct_trivial)
+        );
+    }
+
+    #[test]
+    fn count_minimal_quorums_of_nonsymmetric_fbas_matches_enumeration() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        assert_eq!(
+            find_minimal_quorums(&fbas).len(),
+            count_minimal_quorums(&fbas)
+        );
+    }
+
+    #[test]
+    fn capped_minimal_quorums_search_respects_cap() {
+        let fbas_2019 =
+            Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let mut fbas_2019_asymmetric = fbas_2019.clone();
+        let mut quorum_set = fbas_2019_asymmetric.get_quorum_set(1).unwrap();
+        quorum_set.inner_quorum_sets.pop();
+        fbas_2019_asymmetric.swap_quorum_set(1, quorum_set);
+
+        for fbas in [fbas_2019, fbas_2019_asymmetric] {
+            let all_quorums = find_minimal_quorums(&fbas);
+            let n = all_quorums.len();
+            for max in [1, n - 1, n] {
+                let (quorums, hit_cap) = find_minimal_quorums_capped(&fbas, max);
+                assert_eq!(max.min(n), quorums.len());
+                assert_eq!(n > max, hit_cap);
+                assert!(quorums.iter().all(|quorum| all_quorums.contains(quorum)));
+            }
+        }
+    }
+
     #[test]
     fn nonintersecting_quorums_in_half_half() {
         let fbas = Fbas::from_json_str(
@@ -0,0 +1,249 @@
+use super::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Metrics of a `NodeIdSetVecResult`, as returned by `NodeIdSetVecResult::description`. Serializes
+/// into a JSON object with named fields, unlike the positional tuple returned by
+/// `NodeIdSetVecResult::describe`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetsDescription {
+    /// Number of sets.
+    pub count: usize,
+    /// Number of distinct nodes contained in any of the sets.
+    pub distinct_nodes: usize,
+    pub size: SetSizeDescription,
+    /// Number of sets with size 0, 1, ..., `size.max`.
+    pub histogram: Vec<usize>,
+}
+/// Minimum, maximum and mean set size; see `SetsDescription`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SetSizeDescription {
+    pub min: usize,
+    pub max: usize,
+    pub mean: f64,
+}
+impl From<(usize, usize, (usize, usize, f64), Vec<usize>)> for SetsDescription {
+    fn from(
+        (count, distinct_nodes, (min, max, mean), histogram): (
+            usize,
+            usize,
+            (usize, usize, f64),
+            Vec<usize>,
+        ),
+    ) -> Self {
+        SetsDescription {
+            count,
+            distinct_nodes,
+            size: SetSizeDescription { min, max, mean },
+            histogram,
+        }
+    }
+}
+
+/// Serializable bundle of the results of all standard analyses, roughly what the
+/// `fbas_analyzer` binary outputs when called with `--all --describe`. Sets of node sets are only
+/// included in their "described" form, as they can get very large.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisReport {
+    pub nodes_total: usize,
+    pub has_quorum_intersection: bool,
+    pub top_tier: NodeIdSetResult,
+    pub top_tier_size: usize,
+    pub minimal_quorums: SetsDescription,
+    pub minimal_blocking_sets: SetsDescription,
+    pub minimal_splitting_sets: SetsDescription,
+}
+
+impl Analysis {
+    /// Runs all standard analyses and packages their results in an `AnalysisReport`. If
+    /// `groupings` are passed, all results are merged by group (and reduced to minimal sets).
+    pub fn full_report(&self, groupings: Option<&Groupings>) -> AnalysisReport {
+        let describe = |result: NodeIdSetVecResult| {
+            if let Some(groupings) = groupings {
+                result
+                    .merged_by_group(groupings)
+                    .minimal_sets()
+                    .description()
+            } else {
+                result.description()
+            }
+        };
+        let (nodes_total, top_tier) = if let Some(groupings) = groupings {
+            (
+                self.all_nodes().merged_by_group(groupings).len(),
+                self.top_tier().merged_by_group(groupings),
+            )
+        } else {
+            (self.all_nodes().len(), self.top_tier())
+        };
+        AnalysisReport {
+            nodes_total,
+            has_quorum_intersection: self.has_quorum_intersection(),
+            top_tier_size: top_tier.len(),
+            top_tier,
+            minimal_quorums: describe(self.minimal_quorums()),
+            minimal_blocking_sets: describe(self.minimal_blocking_sets()),
+            minimal_splitting_sets: describe(self.minimal_splitting_sets()),
+        }
+    }
+}
+
+/// Results of the standard analyses for one consensus cluster, computed on the FBAS induced by
+/// the cluster's nodes (i.e., all other nodes are treated as if they didn't exist), or for one
+/// camp of a cluster that lacks quorum intersection (see `Analysis::per_cluster_report`). All
+/// node IDs refer to the complete, analyzed FBAS.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClusterReport {
+    pub nodes: NodeIdSetResult,
+    pub top_tier: NodeIdSetResult,
+    pub minimal_quorums: NodeIdSetVecResult,
+    pub minimal_blocking_sets: NodeIdSetVecResult,
+    pub minimal_splitting_sets: NodeIdSetVecResult,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn full_report_for_correct() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json")).to_core();
+        let analysis = Analysis::new(&fbas);
+
+        let report = analysis.full_report(None);
+        assert!(report.has_quorum_intersection);
+        assert_eq!(bitset![0, 1, 3], report.top_tier.clone().unwrap());
+        assert_eq!(3, report.top_tier_size);
+        assert_eq!(
+            SetsDescription::from((3, 3, (2, 2, 2.0), vec![0, 0, 3])),
+            report.minimal_quorums
+        );
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["has_quorum_intersection"], true);
+        assert_eq!(json["top_tier"], serde_json::json!([0, 1, 3]));
+        assert_eq!(
+            json["minimal_splitting_sets"],
+            serde_json::json!({
+                "count": 4,
+                "distinct_nodes": 4,
+                "size": { "min": 1, "max": 1, "mean": 1.0 },
+                "histogram": [0, 4]
+            })
+        );
+    }
+
+    #[test]
+    fn per_cluster_report_for_two_disjoint_clusters() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 1, "validators": ["n3", "n4"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 2, "validators": ["n3", "n4"] }
+            },
+            {
+                "publicKey": "n5",
+                "quorumSet": { "threshold": 1, "validators": ["n4"] }
+            }
+        ]"#,
+        );
+        let analysis = Analysis::new(&fbas);
+        assert!(!analysis.has_quorum_intersection());
+
+        let reports = analysis.per_cluster_report();
+        assert_eq!(2, reports.len());
+        assert!(reports[0]
+            .nodes
+            .involved_nodes()
+            .is_disjoint(&reports[1].nodes.involved_nodes()));
+        for report in reports.iter() {
+            let nodes = report.nodes.involved_nodes();
+            assert!(report.top_tier.involved_nodes().is_subset(&nodes));
+            assert_eq!(
+                report.top_tier.involved_nodes(),
+                report.minimal_quorums.involved_nodes()
+            );
+            assert!(report
+                .minimal_quorums
+                .clone()
+                .unwrap()
+                .iter()
+                .all(|quorum| fbas.is_quorum(quorum)));
+            assert!(report
+                .minimal_blocking_sets
+                .involved_nodes()
+                .is_subset(&nodes));
+            assert!(!report.minimal_splitting_sets.contains_empty_set());
+        }
+        let mut all_quorums: Vec<NodeIdSet> = reports
+            .into_iter()
+            .flat_map(|report| report.minimal_quorums.unwrap())
+            .collect();
+        all_quorums.sort();
+        let mut expected_quorums = analysis.minimal_quorums().unwrap();
+        expected_quorums.sort();
+        assert_eq!(expected_quorums, all_quorums);
+    }
+
+    #[test]
+    fn per_cluster_report_for_broken_has_one_report_per_camp() {
+        // broken.json lacks quorum intersection but its quorums all live in the same cluster
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let reports = analysis.per_cluster_report();
+        assert_eq!(2, reports.len());
+        for report in reports.iter() {
+            assert_eq!(report.nodes, report.top_tier);
+            assert!(report
+                .minimal_quorums
+                .clone()
+                .unwrap()
+                .iter()
+                .all(|quorum| fbas.is_quorum(quorum)));
+            assert!(all_intersect(&report.minimal_quorums.clone().unwrap()));
+        }
+        assert_eq!(
+            vec![bitset![3, 4], bitset![3, 10], bitset![4, 10]],
+            reports[0].minimal_blocking_sets.clone().unwrap()
+        );
+        assert_eq!(
+            vec![bitset![3], bitset![4], bitset![10]],
+            reports[0].minimal_splitting_sets.clone().unwrap()
+        );
+        assert_eq!(
+            vec![bitset![3, 4], bitset![3, 10], bitset![4, 10]],
+            reports[0].minimal_quorums.clone().unwrap()
+        );
+        assert_eq!(
+            vec![bitset![3, 4], bitset![4, 6], bitset![4, 10]],
+            reports[1].minimal_quorums.clone().unwrap()
+        );
+        let mut all_quorums: Vec<NodeIdSet> = reports
+            .into_iter()
+            .flat_map(|report| report.minimal_quorums.unwrap())
+            .collect();
+        all_quorums.sort();
+        all_quorums.dedup();
+        let mut expected_quorums = analysis.minimal_quorums().unwrap();
+        expected_quorums.sort();
+        assert_eq!(expected_quorums, all_quorums);
+    }
+}
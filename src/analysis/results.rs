@@ -62,6 +62,13 @@ impl From<NodeIdSet> for NodeIdSetResult {
         Self::new(set, None)
     }
 }
+/// Lets set operations like `NodeIdSetResult::difference` take either a `NodeIdSet` or another
+/// `NodeIdSetResult`.
+impl std::borrow::Borrow<NodeIdSet> for NodeIdSetResult {
+    fn borrow(&self) -> &NodeIdSet {
+        &self.node_set
+    }
+}
 
 /// Wraps a vector of node ID sets. Node ID sets are stored in shrunken form to preserve memory.
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
@@ -105,7 +112,8 @@ impl NodeIdSetVecResult {
     pub fn contains_empty_set(&self) -> bool {
         self.shrunken_node_sets.contains(&bitset![])
     }
-    /// Returns (number_of_sets, number_of_distinct_nodes, <minmaxmean_set_size>, <histogram>)
+    /// Returns (number_of_sets, number_of_distinct_nodes, <minmaxmean_set_size>, <histogram>);
+    /// for an empty result, this is `(0, 0, (0, 0, 0.0), [0])`.
     pub fn describe(&self) -> (usize, usize, (usize, usize, f64), Vec<usize>) {
         (
             self.shrunken_node_sets.len(),
@@ -114,11 +122,15 @@ impl NodeIdSetVecResult {
             self.histogram(),
         )
     }
+    /// Like `describe`, but with named fields.
+    pub fn description(&self) -> SetsDescription {
+        self.describe().into()
+    }
     /// Returns (min_set_size, max_set_size, mean_set_size)
     pub fn minmaxmean(&self) -> (usize, usize, f64) {
         (self.min(), self.max(), self.mean())
     }
-    /// Returns the cardinality of the smallest member set
+    /// Returns the cardinality of the smallest member set, or 0 if there are no member sets
     pub fn min(&self) -> usize {
         self.shrunken_node_sets
             .iter()
@@ -126,7 +138,7 @@ impl NodeIdSetVecResult {
             .min()
             .unwrap_or(0)
     }
-    /// Returns the cardinality of the largest member set
+    /// Returns the cardinality of the largest member set, or 0 if there are no member sets
     pub fn max(&self) -> usize {
         self.shrunken_node_sets
             .iter()
@@ -134,7 +146,7 @@ impl NodeIdSetVecResult {
             .max()
             .unwrap_or(0)
     }
-    /// Returns the mean cardinality of all member sets
+    /// Returns the mean cardinality of all member sets, or 0.0 if there are no member sets
     pub fn mean(&self) -> f64 {
         if self.shrunken_node_sets.is_empty() {
             0.0
@@ -146,7 +158,8 @@ impl NodeIdSetVecResult {
                 / (self.shrunken_node_sets.len() as f64)
         }
     }
-    /// Returns [ #members with size 0, #members with size 1, ... , #members with maximum size ]
+    /// Returns [ #members with size 0, #members with size 1, ... , #members with maximum size ];
+    /// `[0]` if there are no member sets
     pub fn histogram(&self) -> Vec<usize> {
         let max = self
             .shrunken_node_sets
@@ -173,6 +186,11 @@ impl NodeIdSetVecResult {
         new.shrunken_node_sets = remove_non_minimal_node_sets(new.shrunken_node_sets);
         new
     }
+    /// `true` if no contained set is a subset of (or equal to) another contained set. Results
+    /// merged via `merged_by_group` often aren't minimal until `.minimal_sets()` is called.
+    pub fn is_minimal(&self) -> bool {
+        is_set_of_minimal_node_sets(&self.shrunken_node_sets)
+    }
     pub fn without_nodes(&self, nodes: &[NodeId]) -> Self {
         let mut unshrunken_node_sets = self.unshrunken_node_sets();
         let nodes: NodeIdSet = nodes.iter().copied().collect();
@@ -194,7 +212,7 @@ impl NodeIdSetVecResult {
         };
         self.without_nodes(&nodes_by_id)
     }
-    fn unshrunken_node_sets(&self) -> Vec<NodeIdSet> {
+    pub(crate) fn unshrunken_node_sets(&self) -> Vec<NodeIdSet> {
         if let Some(unshrink_table) = &self.unshrink_table {
             unshrink_sets(&self.shrunken_node_sets, unshrink_table)
         } else {
@@ -263,6 +281,27 @@ mod tests {
         assert_eq!(expected, actual)
     }
 
+    #[test]
+    fn node_sets_description_has_named_fields() {
+        let node_sets_result = NodeIdSetVecResult::new(
+            vec![
+                bitset![0, 1],
+                bitset![2, 3],
+                bitset![4, 5, 6, 7],
+                bitset![1, 4],
+            ],
+            None,
+        );
+        let actual = serde_json::to_value(node_sets_result.description()).unwrap();
+        let expected = serde_json::json!({
+            "count": 4,
+            "distinct_nodes": 8,
+            "size": { "min": 2, "max": 4, "mean": 2.5 },
+            "histogram": [0, 0, 3, 0, 1]
+        });
+        assert_eq!(expected, actual)
+    }
+
     #[test]
     fn involved_nodes_in_shrunken_result() {
         let shrink_manager = ShrinkManager::new(bitset![23, 42]);
@@ -430,6 +469,37 @@ mod tests {
         assert_eq!(expected, actual);
     }
     #[test]
+    fn merged_results_are_minimal_after_minimal_sets() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "Jim"
+            },
+            {
+                "publicKey": "Jon"
+            },
+            {
+                "publicKey": "Alex"
+            }
+            ]"#,
+        );
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[
+            {
+                "name": "J Mafia",
+                "validators": [ "Jim", "Jon" ]
+            }
+            ]"#,
+            &fbas,
+        );
+        let result = NodeIdSetVecResult::new(bitsetvec![{0, 1}, {0, 2}], None);
+        assert!(result.is_minimal());
+
+        let merged = result.merged_by_group(&organizations);
+        assert!(!merged.is_minimal());
+        assert!(merged.minimal_sets().is_minimal());
+    }
+    #[test]
     fn merge_results_by_country() {
         let fbas_input = r#"[
             {
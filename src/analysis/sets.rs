@@ -14,6 +14,113 @@ pub fn all_intersect(node_sets: &[NodeIdSet]) -> bool {
     }
 }
 
+/// Groups `node_sets` into maximal families of pairwise intersecting sets (i.e., the maximal
+/// cliques of the "intersects" graph). A set can end up in more than one family. Sets within a
+/// family and the families themselves keep the order of `node_sets`. If all sets intersect, this
+/// is a single family containing all of `node_sets`.
+pub(crate) fn maximal_intersecting_families(node_sets: &[NodeIdSet]) -> Vec<Vec<NodeIdSet>> {
+    if node_sets.is_empty() {
+        return vec![];
+    } else if all_intersect(node_sets) {
+        return vec![node_sets.to_vec()];
+    }
+    let neighbours: Vec<BitSet> = node_sets
+        .iter()
+        .enumerate()
+        .map(|(i, x)| {
+            node_sets
+                .iter()
+                .enumerate()
+                .filter(|&(j, y)| i != j && !x.is_disjoint(y))
+                .map(|(j, _)| j)
+                .collect()
+        })
+        .collect();
+    let mut families = vec![];
+    maximal_cliques_finder_step(
+        &mut vec![],
+        (0..node_sets.len()).collect(),
+        BitSet::new(),
+        &neighbours,
+        &mut families,
+    );
+    families.sort_unstable();
+    families
+        .into_iter()
+        .map(|family| family.into_iter().map(|i| node_sets[i].clone()).collect())
+        .collect()
+}
+/// Bron-Kerbosch with pivoting.
+fn maximal_cliques_finder_step(
+    clique: &mut Vec<usize>,
+    mut candidates: BitSet,
+    mut excluded: BitSet,
+    neighbours: &[BitSet],
+    found_cliques: &mut Vec<Vec<usize>>,
+) {
+    if candidates.is_empty() && excluded.is_empty() {
+        let mut found_clique = clique.clone();
+        found_clique.sort_unstable();
+        found_cliques.push(found_clique);
+        return;
+    }
+    let pivot = candidates
+        .union(&excluded)
+        .max_by_key(|&u| candidates.intersection(&neighbours[u]).count())
+        .unwrap();
+    let branches: Vec<usize> = candidates.difference(&neighbours[pivot]).collect();
+    for v in branches {
+        clique.push(v);
+        maximal_cliques_finder_step(
+            clique,
+            candidates.intersection(&neighbours[v]).collect(),
+            excluded.intersection(&neighbours[v]).collect(),
+            neighbours,
+            found_cliques,
+        );
+        clique.pop();
+        candidates.remove(v);
+        excluded.insert(v);
+    }
+}
+
+/// Returns the minimal sets that intersect each set in `node_sets`.
+pub(crate) fn minimal_hitting_sets(node_sets: &[NodeIdSet]) -> Vec<NodeIdSet> {
+    let mut found_hitting_sets = vec![];
+    minimal_hitting_sets_finder_step(&mut bitset![], node_sets, &mut found_hitting_sets);
+    remove_non_minimal_node_sets(found_hitting_sets)
+}
+fn minimal_hitting_sets_finder_step(
+    selection: &mut NodeIdSet,
+    node_sets: &[NodeIdSet],
+    found_hitting_sets: &mut Vec<NodeIdSet>,
+) {
+    if let Some(missed) = node_sets.iter().find(|x| x.is_disjoint(selection)) {
+        for node_id in missed.iter() {
+            selection.insert(node_id);
+            minimal_hitting_sets_finder_step(selection, node_sets, found_hitting_sets);
+            selection.remove(node_id);
+        }
+    } else {
+        found_hitting_sets.push(selection.clone());
+    }
+}
+
+/// Returns the minimal sets among the intersections of each two (different) sets in `node_sets`.
+pub(crate) fn minimal_pairwise_intersections(node_sets: &[NodeIdSet]) -> Vec<NodeIdSet> {
+    let intersections = node_sets
+        .iter()
+        .enumerate()
+        .flat_map(|(i, x)| {
+            node_sets
+                .iter()
+                .skip(i + 1)
+                .map(move |y| x.intersection(y).collect())
+        })
+        .collect();
+    remove_non_minimal_node_sets(intersections)
+}
+
 /// Returns the union of all sets in `node_sets`.
 pub fn involved_nodes(node_sets: &[NodeIdSet]) -> NodeIdSet {
     let mut all_nodes: NodeIdSet = bitset![];
@@ -23,6 +130,15 @@ pub fn involved_nodes(node_sets: &[NodeIdSet]) -> NodeIdSet {
     all_nodes
 }
 
+/// Returns the intersection of all sets in `node_sets`, or the empty set if `node_sets` is empty.
+pub fn common_nodes(node_sets: &[NodeIdSet]) -> NodeIdSet {
+    let mut common_nodes: NodeIdSet = node_sets.first().cloned().unwrap_or_default();
+    for node_set in node_sets.iter().skip(1) {
+        common_nodes.intersect_with(node_set);
+    }
+    common_nodes
+}
+
 /// Does pre- and postprocessing common to most finders
 pub(crate) fn find_minimal_sets<F>(fbas: &Fbas, finder: F) -> Vec<NodeIdSet>
 where
@@ -172,6 +288,49 @@ mod tests {
         assert!(!all_intersect(&[bitset![0], bitset![1, 2]]));
     }
 
+    #[test]
+    fn maximal_intersecting_families_of_broken_quorums() {
+        let quorums = vec![bitset![3, 4], bitset![3, 10], bitset![4, 6], bitset![4, 10]];
+        let expected = vec![
+            vec![bitset![3, 4], bitset![3, 10], bitset![4, 10]],
+            vec![bitset![3, 4], bitset![4, 6], bitset![4, 10]],
+        ];
+        assert_eq!(expected, maximal_intersecting_families(&quorums));
+    }
+
+    #[test]
+    fn maximal_intersecting_families_of_intersecting_or_no_sets() {
+        let quorums = vec![bitset![0, 1], bitset![0, 2], bitset![1, 2]];
+        assert_eq!(
+            vec![quorums.clone()],
+            maximal_intersecting_families(&quorums)
+        );
+        assert!(maximal_intersecting_families(&[]).is_empty());
+    }
+
+    #[test]
+    fn minimal_hitting_sets_of_broken_camp() {
+        let quorums = vec![bitset![3, 4], bitset![4, 6], bitset![4, 10]];
+        let expected = vec![bitset![4], bitset![3, 6, 10]];
+        assert_eq!(expected, minimal_hitting_sets(&quorums));
+    }
+
+    #[test]
+    fn minimal_pairwise_intersections_of_broken_camp() {
+        let quorums = vec![bitset![3, 4], bitset![3, 10], bitset![4, 10]];
+        let expected = vec![bitset![3], bitset![4], bitset![10]];
+        assert_eq!(expected, minimal_pairwise_intersections(&quorums));
+    }
+
+    #[test]
+    fn node_set_common_nodes() {
+        assert_eq!(
+            bitset![0],
+            common_nodes(&[bitset![0, 1], bitset![0, 2], bitset![0, 1, 2]])
+        );
+        assert_eq!(bitset![], common_nodes(&[]));
+    }
+
     #[test]
     fn minimize_node_sets() {
         let non_minimal = vec![bitset![0, 1, 2], bitset![0, 1], bitset![0, 2]];
@@ -1,18 +1,45 @@
 use super::*;
 use itertools::Itertools;
+use std::cell::Cell;
 use std::iter::FromIterator;
 
 /// If the FBAS *doesn't* enjoy quorum intersection, this will just return `bitsetvec![{}]`...
 pub fn find_minimal_splitting_sets(fbas: &Fbas) -> Vec<NodeIdSet> {
+    find_minimal_splitting_sets_reporting_fast_path(fbas, &SymmetricNodesCache::default()).0
+}
+
+/// Like `find_minimal_splitting_sets`, but also returns `true` if no exhaustive search was
+/// needed (e.g., because the minimal splitting sets could be extracted from a symmetric cluster).
+pub(crate) fn find_minimal_splitting_sets_reporting_fast_path(
+    fbas: &Fbas,
+    symmetric_nodes_cache: &SymmetricNodesCache,
+) -> (Vec<NodeIdSet>, bool) {
     info!("Starting to look for minimal splitting sets...");
+    let used_fast_path = Cell::new(true);
     let minimal_splitting_sets = find_minimal_sets(fbas, |clusters, fbas| {
-        minimal_splitting_sets_finder(clusters, fbas)
+        minimal_splitting_sets_finder(clusters, fbas, &used_fast_path, symmetric_nodes_cache)
     });
     info!(
         "Found {} minimal splitting sets.",
         minimal_splitting_sets.len()
     );
-    minimal_splitting_sets
+    (minimal_splitting_sets, used_fast_path.get())
+}
+
+/// Finds one splitting set of minimal size, or `None` if there are no splitting sets. Searches
+/// with an increasing size bound and stops as soon as a splitting set is found, which can be
+/// much faster than enumerating all minimal splitting sets. If there are several smallest
+/// splitting sets, which one is returned is unspecified.
+pub fn find_smallest_splitting_set(fbas: &Fbas) -> Option<NodeIdSet> {
+    info!("Starting to look for a smallest splitting set...");
+    let smallest_splitting_set = find_sets(fbas, smallest_splitting_set_finder)
+        .into_iter()
+        .next();
+    info!(
+        "Found a smallest splitting set of size {:?}.",
+        smallest_splitting_set.as_ref().map(|set| set.len())
+    );
+    smallest_splitting_set
 }
 
 /// Finds all nodes that can potentially make quorums smaller by more than one node (i.e., more
@@ -30,6 +57,8 @@ pub fn find_quorum_expanders(fbas: &Fbas) -> NodeIdSet {
 fn minimal_splitting_sets_finder(
     consensus_clusters: Vec<NodeIdSet>,
     fbas: &Fbas,
+    used_fast_path: &Cell<bool>,
+    symmetric_nodes_cache: &SymmetricNodesCache,
 ) -> Vec<NodeIdSet> {
     // We'll be using `is_symmetric_cluster` multiple times, and it needs quorum sets to be in
     // "standard form".
@@ -49,43 +78,20 @@ fn minimal_splitting_sets_finder(
         let quorum_expanders = find_quorum_expanders(&fbas);
         debug!("Done.");
 
-        // If there are quorum expanders then there might be smaller (and different) splitting sets
-        // than what is suggested by the cluster's defining quorum set.
-        let usable_symmetric_cluster = quorum_expanders
-            .is_empty()
-            .then(|| is_symmetric_cluster(&cluster_nodes, &fbas))
-            .flatten();
-
-        if let Some(symmetric_cluster) = usable_symmetric_cluster {
+        if let Some(symmetric_cluster) =
+            usable_symmetric_cluster(&cluster_nodes, &quorum_expanders, &fbas)
+        {
             debug!("Cluster contains a usable symmetric cluster! Extracting splitting sets...");
             symmetric_cluster.to_minimal_splitting_sets()
         } else {
-            let relevant_nodes: Vec<NodeId> = cluster_nodes.union(&quorum_expanders).collect();
-
-            debug!("Determining the set of affected nodes by each node...");
-            let affected_per_node = find_affected_nodes_per_node(&fbas);
-            debug!("Done.");
+            used_fast_path.set(false);
 
-            debug!("Determining (page) rank scores");
-            // non-cluster nodes will have 0 scores anyway
-            let rank_scores = rank_nodes(&cluster_nodes.iter().collect::<Vec<NodeId>>(), &fbas);
-            debug!("Done.");
-
-            let combined_scores: Vec<RankScore> = rank_scores
-                .into_iter()
-                .enumerate()
-                .map(|(i, score)| {
-                    score + affected_per_node[i].len() as f64 / fbas.number_of_nodes() as f64
-                })
-                .collect();
-
-            debug!("Sorting nodes by combined rank...");
-            let sorted_nodes = sort_by_score(relevant_nodes, &combined_scores);
-            debug!("Sorted.");
-
-            debug!("Looking for symmetric nodes...");
-            let symmetric_nodes = find_symmetric_nodes_in_node_set(&fbas.all_nodes(), &fbas);
-            debug!("Done.");
+            let (sorted_nodes, precomputed) = prepare_splitting_sets_search(
+                &cluster_nodes,
+                &quorum_expanders,
+                &fbas,
+                symmetric_nodes_cache,
+            );
 
             let mut found_splitting_sets = vec![];
 
@@ -94,24 +100,119 @@ fn minimal_splitting_sets_finder(
                 &mut CandidateValues::new(sorted_nodes),
                 &mut found_splitting_sets,
                 FbasValues::new(&fbas),
-                &PrecomputedValues::new(combined_scores, symmetric_nodes.clone()),
+                &precomputed,
             );
             debug!(
                 "Found {} splitting sets. Reducing to minimal splitting sets...",
                 found_splitting_sets.len()
             );
             let minimal_unexpanded_node_sets = remove_non_minimal_node_sets(found_splitting_sets);
-            symmetric_nodes.expand_sets(minimal_unexpanded_node_sets)
+            precomputed
+                .symmetric_nodes
+                .expand_sets(minimal_unexpanded_node_sets)
         }
     }
 }
+fn smallest_splitting_set_finder(
+    consensus_clusters: Vec<NodeIdSet>,
+    fbas: &Fbas,
+) -> Vec<NodeIdSet> {
+    let fbas = fbas.with_standard_form_quorum_sets();
+
+    if consensus_clusters.len() > 1 {
+        debug!("It's clear that we lack quorum intersection; the empty set is a splitting set.");
+        bitsetvec![{}]
+    } else if consensus_clusters.is_empty() {
+        debug!("There aren't any quorums, and hence there are no splitting sets.");
+        bitsetvec![]
+    } else {
+        let cluster_nodes = consensus_clusters.into_iter().next().unwrap();
+        let quorum_expanders = find_quorum_expanders(&fbas);
+
+        if let Some(symmetric_cluster) =
+            usable_symmetric_cluster(&cluster_nodes, &quorum_expanders, &fbas)
+        {
+            debug!("Cluster contains a usable symmetric cluster! Extracting splitting sets...");
+            symmetric_cluster
+                .to_minimal_splitting_sets()
+                .into_iter()
+                .min_by_key(|splitting_set| splitting_set.len())
+                .into_iter()
+                .collect()
+        } else {
+            let (sorted_nodes, mut precomputed) = prepare_splitting_sets_search(
+                &cluster_nodes,
+                &quorum_expanders,
+                &fbas,
+                &SymmetricNodesCache::default(),
+            );
+            precomputed.stop_at_first = true;
+
+            let mut found_splitting_sets = vec![];
+            for max_size in 1..=sorted_nodes.len() {
+                debug!("Looking for splitting sets of size {}...", max_size);
+                precomputed.max_size = max_size;
+                splitting_sets_finder_step(
+                    &mut CandidateValues::new(sorted_nodes.clone()),
+                    &mut found_splitting_sets,
+                    FbasValues::new(&fbas),
+                    &precomputed,
+                );
+                if !found_splitting_sets.is_empty() {
+                    break;
+                }
+            }
+            found_splitting_sets
+        }
+    }
+}
+/// If there are quorum expanders then there might be smaller (and different) splitting sets
+/// than what is suggested by the cluster's defining quorum set.
+fn usable_symmetric_cluster<'a>(
+    cluster_nodes: &NodeIdSet,
+    quorum_expanders: &NodeIdSet,
+    fbas: &'a Fbas,
+) -> Option<&'a QuorumSet> {
+    quorum_expanders
+        .is_empty()
+        .then(|| is_symmetric_cluster(cluster_nodes, fbas))
+        .flatten()
+}
+/// Sorts the nodes relevant for the exhaustive search and precomputes values used in each of its
+/// steps. The returned values don't bound the search.
+fn prepare_splitting_sets_search(
+    cluster_nodes: &NodeIdSet,
+    quorum_expanders: &NodeIdSet,
+    fbas: &Fbas,
+    symmetric_nodes_cache: &SymmetricNodesCache,
+) -> (Vec<NodeId>, PrecomputedValues) {
+    let relevant_nodes: Vec<NodeId> = cluster_nodes.union(quorum_expanders).collect();
+
+    // non-cluster nodes will have 0 rank scores anyway
+    let combined_scores = influence_scores(&cluster_nodes.iter().collect::<Vec<NodeId>>(), fbas);
+
+    debug!("Sorting nodes by combined rank...");
+    let sorted_nodes = sort_by_score(relevant_nodes, &combined_scores);
+    debug!("Sorted.");
+
+    debug!("Looking for symmetric nodes...");
+    let symmetric_nodes = symmetric_nodes_cache.get_or_find(&fbas.all_nodes(), fbas);
+    debug!("Done.");
+
+    (
+        sorted_nodes,
+        PrecomputedValues::new(combined_scores, symmetric_nodes),
+    )
+}
 fn splitting_sets_finder_step(
     candidates: &mut CandidateValues,
     found_splitting_sets: &mut Vec<NodeIdSet>,
     mut fbas: FbasValues,
     precomputed: &PrecomputedValues,
 ) {
-    if fbas.consensus_clusters.is_empty() && !has_potential(candidates, &fbas) {
+    if (precomputed.stop_at_first && !found_splitting_sets.is_empty())
+        || (fbas.consensus_clusters.is_empty() && !has_potential(candidates, &fbas))
+    {
         // return
     } else if fbas.consensus_clusters_changed && !fbas.has_quorum_intersection(precomputed) {
         found_splitting_sets.push(candidates.selection.clone());
@@ -126,13 +227,14 @@ fn splitting_sets_finder_step(
         // We require that symmetric nodes are used in a fixed order; this way we can omit
         // redundant branches (we expand all combinations of symmetric nodes in the final result
         // sets).
-        if precomputed
-            .symmetric_nodes
-            .is_non_redundant_next(current_candidate, &candidates.selection)
+        if candidates.selection.len() < precomputed.max_size
+            && precomputed
+                .symmetric_nodes
+                .is_non_redundant_next(current_candidate, &candidates.selection)
         {
             candidates.selection.insert(current_candidate);
 
-            let modified_fbas = fbas.clone_assuming_faulty(&bitset![current_candidate]);
+            let modified_fbas = fbas.clone_assuming_faulty(bitset![current_candidate]);
 
             splitting_sets_finder_step(
                 candidates,
@@ -209,15 +311,18 @@ impl<'a> FbasValues<'a> {
             }
         }
     }
-    fn clone_assuming_faulty(&self, faulty_nodes: &NodeIdSet) -> Self {
-        let mut new_faulty_nodes = faulty_nodes.clone();
+    /// Takes `faulty_nodes` by value so that callers that build it anyway don't cause an extra
+    /// allocation here.
+    fn clone_assuming_faulty(&self, faulty_nodes: NodeIdSet) -> Self {
+        let mut new_faulty_nodes = faulty_nodes;
         new_faulty_nodes.difference_with(&self.faulty_nodes);
 
         let mut faulty_nodes = self.faulty_nodes.clone();
         faulty_nodes.union_with(&new_faulty_nodes);
 
+        // `self.fbas` already reflects `self.faulty_nodes`
         let mut fbas = self.fbas.clone();
-        fbas.assume_split_faulty(&faulty_nodes);
+        fbas.assume_split_faulty(&new_faulty_nodes);
 
         // sccs can't become bigger by adding faulty nodes
         let sccs = self
@@ -257,12 +362,16 @@ impl<'a> FbasValues<'a> {
 struct PrecomputedValues {
     ranking_scores: Vec<RankScore>,
     symmetric_nodes: SymmetricNodesMap, // maintained for relevance to splitting sets
+    max_size: usize,
+    stop_at_first: bool,
 }
 impl PrecomputedValues {
     fn new(ranking_scores: Vec<RankScore>, symmetric_nodes: SymmetricNodesMap) -> Self {
         Self {
             ranking_scores,
             symmetric_nodes,
+            max_size: usize::MAX,
+            stop_at_first: false,
         }
     }
 }
@@ -367,7 +476,7 @@ fn has_potential(candidates: &CandidateValues, fbas: &FbasValues) -> bool {
     )
         ||
     // the remaining nodes could split off some other nodes
-    fbas.clone_assuming_faulty(remaining)
+    fbas.clone_assuming_faulty(remaining.clone())
         .consensus_clusters_changed
 }
 
@@ -434,6 +543,28 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn smallest_splitting_set_has_size_of_smallest_minimal_splitting_set() {
+        let fbases = [
+            Fbas::from_json_file(Path::new("test_data/correct.json")).to_core(),
+            Fbas::from_json_file(Path::new("test_data/correct_trivial.json")),
+            Fbas::from_json_file(Path::new("test_data/broken_trivial.json")),
+            Fbas::from_json_file(Path::new("test_data/mobilecoin_nodes_2021-10-22.json")),
+            Fbas::new(),
+        ];
+        for fbas in fbases.iter() {
+            let minimal_splitting_sets = find_minimal_splitting_sets(fbas);
+            let smallest_splitting_set = find_smallest_splitting_set(fbas);
+            assert_eq!(
+                minimal_splitting_sets.iter().map(|set| set.len()).min(),
+                smallest_splitting_set.as_ref().map(|set| set.len())
+            );
+            if let Some(splitting_set) = smallest_splitting_set {
+                assert!(minimal_splitting_sets.contains(&splitting_set));
+            }
+        }
+    }
+
     #[test]
     fn minimal_splitting_sets_in_different_consensus_clusters() {
         let fbas = Fbas::from_json_str(
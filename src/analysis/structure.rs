@@ -0,0 +1,188 @@
+use super::*;
+
+use serde::Serialize;
+
+/// Structural breakdown of an FBAS, as returned by `Analysis::structural_summary`. All node IDs
+/// refer to the analyzed (unshrunken) FBAS.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StructuralSummary {
+    /// Nodes whose quorum sets can never be satisfied; these are not part of any component.
+    pub unsatisfiable_nodes: NodeSetSummary,
+    /// Strongly connected components of the satisfiable nodes, largest first.
+    pub strongly_connected_components: Vec<ComponentSummary>,
+    /// Union of the top tiers of all consensus clusters.
+    pub top_tier: NodeSetSummary,
+}
+
+/// One strongly connected component of a `StructuralSummary`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComponentSummary {
+    pub nodes: NodeSetSummary,
+    /// `true` if the component contains quorums.
+    pub is_consensus_cluster: bool,
+    /// Symmetric clusters found in this component; always empty if it isn't a consensus
+    /// cluster.
+    pub symmetric_clusters: Vec<NodeSetSummary>,
+    /// Top tier of this component; `None` if it isn't a consensus cluster.
+    pub top_tier: Option<NodeSetSummary>,
+}
+
+/// A node set, as node IDs and (optionally) public keys.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeSetSummary {
+    pub ids: Vec<NodeId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_keys: Option<Vec<PublicKey>>,
+}
+
+impl Analysis {
+    /// Strongly connected components, consensus clusters, symmetric clusters and top tier(s), all
+    /// in one structure. Public keys are included next to node IDs if `with_public_keys` is set.
+    pub fn structural_summary(&self, with_public_keys: bool) -> StructuralSummary {
+        let fbas = self.fbas();
+        let summarize = |node_set: &NodeIdSet| NodeSetSummary {
+            ids: node_set.iter().collect(),
+            public_keys: if with_public_keys {
+                Some(to_public_keys(node_set, fbas))
+            } else {
+                None
+            },
+        };
+        let minimal_quorums = self.minimal_quorums().unwrap();
+
+        let mut sccs =
+            partition_into_strongly_connected_components(&fbas.satisfiable_nodes(), fbas);
+        sccs.sort_unstable();
+        sccs.sort_by_key(|scc| std::cmp::Reverse(scc.len()));
+
+        let strongly_connected_components = sccs
+            .iter()
+            .map(|scc| {
+                if contains_quorum(scc, fbas) {
+                    let quorums_in_scc: Vec<NodeIdSet> = minimal_quorums
+                        .iter()
+                        .filter(|quorum| quorum.is_subset(scc))
+                        .cloned()
+                        .collect();
+                    let mut symmetric_clusters = find_symmetric_clusters_in_node_set(scc, fbas);
+                    symmetric_clusters.sort_unstable();
+                    ComponentSummary {
+                        nodes: summarize(scc),
+                        is_consensus_cluster: true,
+                        symmetric_clusters: symmetric_clusters
+                            .iter()
+                            .map(|qset| summarize(&qset.contained_nodes()))
+                            .collect(),
+                        top_tier: Some(summarize(&involved_nodes(&quorums_in_scc))),
+                    }
+                } else {
+                    ComponentSummary {
+                        nodes: summarize(scc),
+                        is_consensus_cluster: false,
+                        symmetric_clusters: vec![],
+                        top_tier: None,
+                    }
+                }
+            })
+            .collect();
+
+        StructuralSummary {
+            unsatisfiable_nodes: summarize(&fbas.unsatisfiable_nodes()),
+            strongly_connected_components,
+            top_tier: summarize(&self.top_tier().unwrap()),
+        }
+    }
+    /// `structural_summary` (node IDs only) as a JSON string.
+    pub fn structural_summary_json(&self) -> String {
+        serde_json::to_string(&self.structural_summary(false)).expect("Error serializing summary")
+    }
+    /// `structural_summary` (node IDs and public keys) as a pretty-printed JSON string.
+    pub fn structural_summary_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(&self.structural_summary(true))
+            .expect("Error serializing summary")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn structural_summary_of_broken_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let summary = analysis.structural_summary(false);
+        let consensus_clusters: Vec<&ComponentSummary> = summary
+            .strongly_connected_components
+            .iter()
+            .filter(|scc| scc.is_consensus_cluster)
+            .collect();
+
+        assert_eq!(1, consensus_clusters.len());
+        assert_eq!(
+            analysis.top_tier().into_vec(),
+            consensus_clusters[0].top_tier.clone().unwrap().ids
+        );
+        assert!(summary.strongly_connected_components[0]
+            .nodes
+            .public_keys
+            .is_none());
+    }
+
+    #[test]
+    fn structural_summary_json_snapshot_for_2019_stellar_network() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&analysis.structural_summary_json()).unwrap();
+        let top_tier = serde_json::json!({
+            "ids": [1, 4, 8, 23, 29, 36, 37, 43, 44, 52, 56, 69, 86, 105, 167, 168, 171]
+        });
+        let sccs = json["strongly_connected_components"].as_array().unwrap();
+
+        assert_eq!(
+            97,
+            json["unsatisfiable_nodes"]["ids"].as_array().unwrap().len()
+        );
+        assert_eq!(51, sccs.len());
+        assert_eq!(top_tier, json["top_tier"]);
+        assert_eq!(
+            serde_json::json!({
+                "nodes": top_tier,
+                "is_consensus_cluster": true,
+                "symmetric_clusters": [top_tier],
+                "top_tier": top_tier,
+            }),
+            sccs[0]
+        );
+        assert_eq!(
+            serde_json::json!({
+                "nodes": { "ids": [3, 30, 134] },
+                "is_consensus_cluster": false,
+                "symmetric_clusters": [],
+                "top_tier": null,
+            }),
+            sccs[1]
+        );
+        assert!(sccs[1..]
+            .iter()
+            .all(|scc| scc["is_consensus_cluster"] == false));
+    }
+
+    #[test]
+    fn structural_summary_json_pretty_includes_public_keys() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let analysis = Analysis::new(&fbas);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&analysis.structural_summary_json_pretty()).unwrap();
+
+        assert_eq!(
+            serde_json::json!(to_public_keys(&fbas.all_nodes(), &fbas)),
+            json["top_tier"]["public_keys"]
+        );
+    }
+}
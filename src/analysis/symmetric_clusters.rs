@@ -103,7 +103,7 @@ impl QuorumSet {
             self.threshold += 1;
         }
     }
-    fn ensure_sorted(&mut self) {
+    pub(crate) fn ensure_sorted(&mut self) {
         self.validators.sort_unstable();
         for qset in self.inner_quorum_sets.iter_mut() {
             qset.ensure_sorted();
@@ -113,6 +113,23 @@ impl QuorumSet {
 }
 
 impl Fbas {
+    /// If `nodes` form a symmetric cluster, i.e., all of them have (logically) the same quorum set
+    /// and that quorum set is comprised of exactly `nodes`, return the common quorum set. Else
+    /// return `None`. Quorum sets are brought into "standard form" (sorted and including the node
+    /// itself) before comparing them, so the returned quorum set is in standard form as well.
+    pub fn is_symmetric_cluster(&self, nodes: &NodeIdSet) -> Option<QuorumSet> {
+        let mut standard_form_qsets = nodes
+            .iter()
+            .map(|node_id| self.nodes[node_id].quorum_set.to_standard_form(node_id));
+        let cluster_quorum_set = standard_form_qsets.next()?;
+        if cluster_quorum_set.contained_nodes().eq(nodes)
+            && standard_form_qsets.all(|qset| qset.eq(&cluster_quorum_set))
+        {
+            Some(cluster_quorum_set)
+        } else {
+            None
+        }
+    }
     pub(crate) fn with_standard_form_quorum_sets(&self) -> Self {
         let mut fbas = self.clone();
         for (node_id, node) in fbas.nodes.iter_mut().enumerate() {
@@ -250,6 +267,50 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+    #[test]
+    fn fbas_is_symmetric_cluster_in_correct_trivial() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+
+        let expected = Some(QuorumSet {
+            validators: vec![0, 1, 2],
+            threshold: 2,
+            inner_quorum_sets: vec![],
+        });
+        assert_eq!(expected, fbas.is_symmetric_cluster(&bitset![0, 1, 2]));
+        assert_eq!(None, fbas.is_symmetric_cluster(&bitset![0, 1]));
+        assert_eq!(None, fbas.is_symmetric_cluster(&bitset![]));
+    }
+
+    #[test]
+    fn fbas_is_symmetric_cluster_applies_standard_form() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n1", "n0"] }
+            }
+        ]"#,
+        );
+        let expected = Some(QuorumSet {
+            validators: vec![0, 1],
+            threshold: 2,
+            inner_quorum_sets: vec![],
+        });
+        assert_eq!(expected, fbas.is_symmetric_cluster(&bitset![0, 1]));
+    }
+
+    #[test]
+    fn fbas_is_symmetric_cluster_in_non_symmetric_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let top_tier = Analysis::new(&fbas).top_tier().unwrap();
+
+        assert_eq!(None, fbas.is_symmetric_cluster(&top_tier));
+    }
+
     #[test]
     fn symmetric_top_tier_in_symmetric_fbas() {
         let fbas = Fbas::from_json_str(
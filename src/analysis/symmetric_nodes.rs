@@ -1,5 +1,13 @@
 use super::*;
 use itertools::Itertools;
+use std::cell::RefCell;
+
+#[cfg(test)]
+thread_local! {
+    /// Number of calls to `find_symmetric_nodes_in_node_set`, for testing caching.
+    pub(crate) static FIND_SYMMETRIC_NODES_CALLS: std::cell::Cell<usize> =
+        const { std::cell::Cell::new(0) };
+}
 
 /// Find groups of nodes that can be freely exchanged with one another. We currently don't find all
 /// such groups of nodes but only such nodes that are always included in the same
@@ -9,6 +17,9 @@ pub(crate) fn find_symmetric_nodes_in_node_set(
     nodes: &NodeIdSet,
     fbas: &Fbas,
 ) -> SymmetricNodesMap {
+    #[cfg(test)]
+    FIND_SYMMETRIC_NODES_CALLS.with(|calls| calls.set(calls.get() + 1));
+
     let mut referencing_qsets = vec![BTreeSet::new(); fbas.number_of_nodes()];
 
     for vc_qset in nodes
@@ -85,6 +96,22 @@ impl SymmetricNodesMap {
     }
 }
 
+/// Remembers the result of `find_symmetric_nodes_in_node_set` per node set, so that several
+/// analyses of the same FBAS don't have to find the same symmetric nodes again. As symmetric nodes
+/// are found based on quorum sets in standard form, results can be shared between an FBAS and
+/// its `with_standard_form_quorum_sets` version, but not between different FBASs.
+#[derive(Debug, Default)]
+pub(crate) struct SymmetricNodesCache(RefCell<HashMap<NodeIdSet, SymmetricNodesMap>>);
+impl SymmetricNodesCache {
+    pub(crate) fn get_or_find(&self, nodes: &NodeIdSet, fbas: &Fbas) -> SymmetricNodesMap {
+        self.0
+            .borrow_mut()
+            .entry(nodes.clone())
+            .or_insert_with(|| find_symmetric_nodes_in_node_set(nodes, fbas))
+            .clone()
+    }
+}
+
 fn expand_symmetric_nodes_in_set(
     unexpanded_set: BitSet,
     matching_symmetric_nodes: Vec<NodeIdSet>,
@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+/// A problem noticed while running analyses, as returned by `Analysis::warnings`. Lets library
+/// users surface issues without having to capture log output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AnalysisWarning {
+    pub kind: AnalysisWarningKind,
+    /// Human-readable description; its wording may change between versions, unlike `kind`.
+    pub message: String,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum AnalysisWarningKind {
+    /// Some nodes have quorum sets that can never be satisfied; they are ignored in analyses.
+    UnsatisfiableNodes,
+    /// The FBAS lacks quorum intersection (or has no quorums at all), so that most other results
+    /// have to be interpreted with care.
+    NoQuorumIntersection,
+    /// A search was stopped early because it hit a caller-provided cap, so that its results are
+    /// incomplete.
+    SearchCapped,
+}
@@ -11,6 +11,7 @@ use std::collections::BTreeMap;
 use std::error::Error;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use csv::{Reader, Writer};
 use par_map::ParMap;
@@ -55,6 +56,40 @@ struct Cli {
     #[structopt(long = "only-core-nodes")]
     only_core_nodes: bool,
 
+    /// Don't search for minimal quorums; `mq_*` fields are set to 0 and `*_mq_*` fields are left
+    /// empty. Quorum intersection is then checked without enumerating minimal quorums.
+    #[structopt(long = "skip-quorums")]
+    skip_quorums: bool,
+
+    /// Don't search for minimal blocking sets; `mbs_*` fields are set to 0 and `*_mbs_*` fields
+    /// are left empty. If minimal quorums are skipped as well, they are still needed for
+    /// determining the top tier.
+    #[structopt(long = "skip-blocking-sets")]
+    skip_blocking_sets: bool,
+
+    /// Don't search for minimal splitting sets; `mss_*` fields are set to 0 and `*_mss_*` fields
+    /// are left empty.
+    #[structopt(long = "skip-splitting-sets")]
+    skip_splitting_sets: bool,
+
+    /// Only compute a single metric per input file and output it as a two-column CSV
+    /// (label, metric), skipping all other analyses. One of "has_quorum_intersection",
+    /// "top_tier_size", "mq_min", "mbs_min" or "mss_min". Can't be combined with `--update`.
+    #[structopt(short = "m", long = "metric")]
+    metric: Option<Metric>,
+
+    /// Additionally write a CSV summary of how often each node is part of the top tier to this
+    /// file. Nodes are matched across input files by their public keys. Columns are `public_key`,
+    /// `top_tier_count` (number of input files in whose top tier the node is) and
+    /// `top_tier_frequency` (`top_tier_count` divided by the number of input files). Only nodes
+    /// that are part of at least one top tier are listed, ordered by descending frequency and
+    /// then by public key. Can't be combined with `--update` or `--metric`.
+    #[structopt(
+        long = "top-tier-frequencies",
+        raw(conflicts_with_all = r#"&["update", "metric"]"#)
+    )]
+    top_tier_frequencies_path: Option<PathBuf>,
+
     /// Number of threads to use. Defaults to 1.
     #[structopt(short = "j", long = "jobs", default_value = "1")]
     jobs: usize,
@@ -68,6 +103,35 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let inputs: Vec<InputDataPoint> = extract_inputs(&args.input_paths, &args.ignore_for_label)?;
 
+    let prep_opts = PreprocessingOptions::new(
+        args.ignore_inactive_nodes,
+        args.ignore_one_node_quorums,
+        args.only_core_nodes,
+    );
+
+    if let Some(metric) = args.metric {
+        if args.update {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Updating existing results is not supported when computing a single metric.",
+            )));
+        }
+        let output_iterator = bulk_compute_metric(inputs, metric, prep_opts, args.jobs);
+        write_csv(output_iterator, &args.output_path, false)?;
+        return Ok(());
+    }
+
+    if let Some(path) = args
+        .top_tier_frequencies_path
+        .as_ref()
+        .filter(|p| p.exists())
+    {
+        return Err(Box::new(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{:?} exists, refusing to overwrite.", path),
+        )));
+    }
+
     let existing_outputs = if args.update {
         load_existing_outputs(&args.output_path)?
     } else {
@@ -76,14 +140,27 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let tasks = make_sorted_tasklist(inputs, existing_outputs);
 
-    let prep_opts = PreprocessingOptions::new(
-        args.ignore_inactive_nodes,
-        args.ignore_one_node_quorums,
-        args.only_core_nodes,
+    let analysis_opts = AnalysisOptions::new(
+        args.skip_quorums,
+        args.skip_blocking_sets,
+        args.skip_splitting_sets,
     );
 
-    let output_iterator = bulk_do(tasks, prep_opts, args.jobs);
+    let mut top_tiers: Vec<Vec<String>> = vec![];
+    let output_iterator =
+        bulk_do(tasks, prep_opts, analysis_opts, args.jobs).map(|(output, top_tier)| {
+            top_tiers.extend(top_tier);
+            output
+        });
     write_csv(output_iterator, &args.output_path, args.update)?;
+
+    if args.top_tier_frequencies_path.is_some() {
+        write_csv(
+            top_tier_frequencies(&top_tiers),
+            &args.top_tier_frequencies_path,
+            false,
+        )?;
+    }
     Ok(())
 }
 
@@ -93,6 +170,10 @@ struct InputDataPoint {
     nodes_path: PathBuf,
     organizations_path: Option<PathBuf>,
 }
+/// Version of the `OutputDataPoint` CSV schema. Increment whenever its fields change, so that
+/// `--update` refuses to extend result files that were written with a different schema.
+const RESULT_SCHEMA_VERSION: u32 = 2;
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct OutputDataPoint {
     label: String,
@@ -101,6 +182,8 @@ struct OutputDataPoint {
     mbs_min: usize,
     mbs_max: usize,
     mbs_mean: f64,
+    /// `mbs_min - 1`; empty if blocking sets were skipped or liveness is already blocked.
+    fault_tolerance: Option<usize>,
     mss_min: usize,
     mss_max: usize,
     mss_mean: f64,
@@ -111,6 +194,8 @@ struct OutputDataPoint {
     orgs_mbs_min: Option<usize>,
     orgs_mbs_max: Option<usize>,
     orgs_mbs_mean: Option<f64>,
+    /// Like `fault_tolerance`, but counting failed organizations instead of failed nodes.
+    orgs_fault_tolerance: Option<usize>,
     orgs_mss_min: Option<usize>,
     orgs_mss_max: Option<usize>,
     orgs_mss_mean: Option<f64>,
@@ -142,6 +227,13 @@ struct OutputDataPoint {
     analysis_duration_mbs: f64,
     analysis_duration_mss: f64,
     analysis_duration_total: f64,
+    result_schema_version: u32,
+}
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct TopTierFrequency {
+    public_key: String,
+    top_tier_count: usize,
+    top_tier_frequency: f64,
 }
 #[derive(Debug, Clone, Copy)]
 struct PreprocessingOptions {
@@ -162,6 +254,21 @@ impl PreprocessingOptions {
         }
     }
 }
+#[derive(Debug, Clone, Copy)]
+struct AnalysisOptions {
+    skip_quorums: bool,
+    skip_blocking_sets: bool,
+    skip_splitting_sets: bool,
+}
+impl AnalysisOptions {
+    pub fn new(skip_quorums: bool, skip_blocking_sets: bool, skip_splitting_sets: bool) -> Self {
+        Self {
+            skip_quorums,
+            skip_blocking_sets,
+            skip_splitting_sets,
+        }
+    }
+}
 type AnalysisResults = (
     Option<usize>,
     Option<usize>,
@@ -182,6 +289,55 @@ macro_rules! extend_output_data_point {
         }
     }};
 }
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Metric {
+    HasQuorumIntersection,
+    TopTierSize,
+    MqMin,
+    MbsMin,
+    MssMin,
+}
+impl Metric {
+    fn name(&self) -> &'static str {
+        match self {
+            Metric::HasQuorumIntersection => "has_quorum_intersection",
+            Metric::TopTierSize => "top_tier_size",
+            Metric::MqMin => "mq_min",
+            Metric::MbsMin => "mbs_min",
+            Metric::MssMin => "mss_min",
+        }
+    }
+    fn compute(&self, analysis: &Analysis) -> String {
+        match self {
+            Metric::HasQuorumIntersection => analysis.has_quorum_intersection().to_string(),
+            Metric::TopTierSize => analysis.top_tier().len().to_string(),
+            Metric::MqMin => analysis.minimal_quorums().min().to_string(),
+            Metric::MbsMin => analysis.minimal_blocking_sets().min().to_string(),
+            Metric::MssMin => analysis.minimal_splitting_sets().min().to_string(),
+        }
+    }
+}
+impl FromStr for Metric {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Metric::HasQuorumIntersection,
+            Metric::TopTierSize,
+            Metric::MqMin,
+            Metric::MbsMin,
+            Metric::MssMin,
+        ]
+        .into_iter()
+        .find(|metric| metric.name() == s)
+        .ok_or_else(|| {
+            format!(
+                "Unknown metric '{}'; expected one of: has_quorum_intersection, top_tier_size, \
+                mq_min, mbs_min, mss_min",
+                s
+            )
+        })
+    }
+}
 #[derive(Debug)]
 #[allow(clippy::large_enum_variant)]
 enum Task {
@@ -255,27 +411,41 @@ fn make_sorted_tasklist(
     tasks
 }
 
+/// Yields one output data point per task, plus the public keys of the top tier nodes for all
+/// tasks that weren't reused from existing outputs.
 fn bulk_do(
     tasks: Vec<Task>,
     prep_opts: PreprocessingOptions,
+    analysis_opts: AnalysisOptions,
     jobs: usize,
-) -> impl Iterator<Item = OutputDataPoint> {
+) -> impl Iterator<Item = (OutputDataPoint, Option<Vec<String>>)> {
     tasks
         .into_iter()
         .with_nb_threads(jobs)
-        .par_map(move |task| analyze_or_reuse(task, prep_opts))
+        .par_map(move |task| analyze_or_reuse(task, prep_opts, analysis_opts))
 }
-fn analyze_or_reuse(task: Task, prep_opts: PreprocessingOptions) -> OutputDataPoint {
+fn analyze_or_reuse(
+    task: Task,
+    prep_opts: PreprocessingOptions,
+    analysis_opts: AnalysisOptions,
+) -> (OutputDataPoint, Option<Vec<String>>) {
     match task {
         Task::Reuse(output) => {
             eprintln!("Reusing existing analysis results for {}.", output.label);
-            output
+            (output, None)
+        }
+        Task::Analyze(input) => {
+            let (output, top_tier) = analyze(input, prep_opts, analysis_opts);
+            (output, Some(top_tier))
         }
-        Task::Analyze(input) => analyze(input, prep_opts),
     }
 }
-fn analyze(input: InputDataPoint, prep_opts: PreprocessingOptions) -> OutputDataPoint {
-    let (result_without_total_duration, analysis_duration_total) = timed_secs!({
+fn analyze(
+    input: InputDataPoint,
+    prep_opts: PreprocessingOptions,
+    analysis_opts: AnalysisOptions,
+) -> (OutputDataPoint, Vec<String>) {
+    let ((result_without_total_duration, top_tier), analysis_duration_total) = timed_secs!({
         let fbas = load_fbas(&input.nodes_path, prep_opts);
         let organizations = maybe_load_organizations(input.organizations_path.as_ref(), &fbas);
         let isps = maybe_load_isps(&input.nodes_path, &fbas);
@@ -285,20 +455,33 @@ fn analyze(input: InputDataPoint, prep_opts: PreprocessingOptions) -> OutputData
         let label = input.label.clone();
 
         let ((mq_min, mq_max, mq_mean), analysis_duration_mq) =
-            timed_secs!(analysis.minimal_quorums().minmaxmean());
+            timed_minmaxmean_unless_skipped(analysis_opts.skip_quorums, || {
+                analysis.minimal_quorums()
+            });
 
-        let has_quorum_intersection = analysis.has_quorum_intersection();
-        let top_tier_size = analysis.top_tier().len();
+        let has_quorum_intersection = if analysis_opts.skip_quorums {
+            analysis.has_quorum_intersection_via_alternative_check().0
+        } else {
+            analysis.has_quorum_intersection()
+        };
 
         let ((mbs_min, mbs_max, mbs_mean), analysis_duration_mbs) =
-            timed_secs!(analysis.minimal_blocking_sets().minmaxmean());
+            timed_minmaxmean_unless_skipped(analysis_opts.skip_blocking_sets, || {
+                analysis.minimal_blocking_sets()
+            });
+
+        // after blocking sets, so that the top tier can be derived from them if quorums are skipped
+        let top_tier = analysis.top_tier();
+        let top_tier_size = top_tier.len();
 
         let ((mss_min, mss_max, mss_mean), analysis_duration_mss) =
-            timed_secs!(analysis.minimal_splitting_sets().minmaxmean());
+            timed_minmaxmean_unless_skipped(analysis_opts.skip_splitting_sets, || {
+                analysis.minimal_splitting_sets()
+            });
 
-        let orgs_output = maybe_merge_sets(&analysis, organizations);
-        let isps_output = maybe_merge_sets(&analysis, isps);
-        let ctries_output = maybe_merge_sets(&analysis, countries);
+        let orgs_output = maybe_merge_sets(&analysis, organizations, analysis_opts);
+        let isps_output = maybe_merge_sets(&analysis, isps, analysis_opts);
+        let ctries_output = maybe_merge_sets(&analysis, countries, analysis_opts);
         let standard_form_hash = hex::encode(Sha3_256::digest(
             &fbas.to_standard_form().to_json_string().into_bytes(),
         ));
@@ -309,37 +492,120 @@ fn analyze(input: InputDataPoint, prep_opts: PreprocessingOptions) -> OutputData
             mbs_min,
             mbs_max,
             mbs_mean,
+            fault_tolerance: fault_tolerance_unless_skipped(
+                analysis_opts.skip_blocking_sets,
+                Some(mbs_min),
+            ),
             mss_min,
             mss_max,
             mss_mean,
             mq_min,
             mq_max,
             mq_mean,
+            result_schema_version: RESULT_SCHEMA_VERSION,
             ..Default::default()
         };
         output = extend_output_with_orgs_results(&orgs_output, output);
+        output.orgs_fault_tolerance =
+            fault_tolerance_unless_skipped(analysis_opts.skip_blocking_sets, output.orgs_mbs_min);
         output = extend_output_with_isps_results(&isps_output, output);
         output = extend_output_with_ctries_results(&ctries_output, output);
+        (
+            OutputDataPoint {
+                standard_form_hash,
+                analysis_duration_mq,
+                analysis_duration_mbs,
+                analysis_duration_mss,
+                analysis_duration_total: 0.0,
+                ..output
+            },
+            top_tier.into_pretty_vec(&fbas, None),
+        )
+    });
+    (
         OutputDataPoint {
-            standard_form_hash,
-            analysis_duration_mq,
-            analysis_duration_mbs,
-            analysis_duration_mss,
-            analysis_duration_total: 0.0,
-            ..output
+            analysis_duration_total,
+            ..result_without_total_duration
+        },
+        top_tier,
+    )
+}
+
+/// Counts, for each public key, in how many of the `top_tiers` it is contained.
+fn top_tier_frequencies(top_tiers: &[Vec<String>]) -> Vec<TopTierFrequency> {
+    let mut counts: BTreeMap<&String, usize> = BTreeMap::new();
+    for top_tier in top_tiers.iter() {
+        for public_key in top_tier.iter() {
+            *counts.entry(public_key).or_insert(0) += 1;
         }
-    });
-    OutputDataPoint {
-        analysis_duration_total,
-        ..result_without_total_duration
     }
+    let mut frequencies: Vec<TopTierFrequency> = counts
+        .into_iter()
+        .map(|(public_key, top_tier_count)| TopTierFrequency {
+            public_key: public_key.clone(),
+            top_tier_count,
+            top_tier_frequency: top_tier_count as f64 / top_tiers.len() as f64,
+        })
+        .collect();
+    // stable sort, so ties stay ordered by public key
+    frequencies.sort_by_key(|frequency| std::cmp::Reverse(frequency.top_tier_count));
+    frequencies
 }
 
-fn maybe_merge_sets(analysis: &Analysis, grouping: Option<Groupings>) -> AnalysisResults {
+fn bulk_compute_metric(
+    mut inputs: Vec<InputDataPoint>,
+    metric: Metric,
+    prep_opts: PreprocessingOptions,
+    jobs: usize,
+) -> impl Iterator<Item = (String, String)> {
+    inputs.sort_by_cached_key(|input| input.label.clone());
+    let header = (String::from("label"), String::from(metric.name()));
+    let data_points = inputs
+        .into_iter()
+        .with_nb_threads(jobs)
+        .par_map(move |input| {
+            let fbas = load_fbas(&input.nodes_path, prep_opts);
+            let analysis = Analysis::new(&fbas);
+            (input.label, metric.compute(&analysis))
+        });
+    std::iter::once(header).chain(data_points)
+}
+
+fn timed_minmaxmean_unless_skipped(
+    skip: bool,
+    sets: impl FnOnce() -> NodeIdSetVecResult,
+) -> ((usize, usize, f64), f64) {
+    if skip {
+        ((0, 0, 0.0), 0.0)
+    } else {
+        timed_secs!(sets().minmaxmean())
+    }
+}
+
+/// The number of failures that can be tolerated without losing liveness, given the size of the
+/// smallest minimal blocking set. `None` if blocking sets were skipped or if the smallest minimal
+/// blocking set is empty, i.e., if there is no liveness to lose.
+fn fault_tolerance_unless_skipped(skip: bool, mbs_min: Option<usize>) -> Option<usize> {
+    if skip {
+        None
+    } else {
+        mbs_min.and_then(|mbs_min| mbs_min.checked_sub(1))
+    }
+}
+
+fn maybe_merge_sets(
+    analysis: &Analysis,
+    grouping: Option<Groupings>,
+    analysis_opts: AnalysisOptions,
+) -> AnalysisResults {
     if let Some(ref group) = grouping {
-        let merge_fix = |sets: NodeIdSetVecResult| {
-            let (min, max, mean) = sets.merged_by_group(group).minimal_sets().minmaxmean();
-            (Some(min), Some(max), Some(mean))
+        let merge_fix = |skip: bool, sets: &dyn Fn() -> NodeIdSetVecResult| {
+            if skip {
+                (None, None, None)
+            } else {
+                let (min, max, mean) = sets().merged_by_group(group).minimal_sets().minmaxmean();
+                (Some(min), Some(max), Some(mean))
+            }
         };
         let (
             top_tier_size,
@@ -348,9 +614,13 @@ fn maybe_merge_sets(analysis: &Analysis, grouping: Option<Groupings>) -> Analysi
             (mss_min, mss_max, mss_mean),
         ) = (
             Some(analysis.top_tier().merged_by_group(group).len()),
-            merge_fix(analysis.minimal_quorums()),
-            merge_fix(analysis.minimal_blocking_sets()),
-            merge_fix(analysis.minimal_splitting_sets()),
+            merge_fix(analysis_opts.skip_quorums, &|| analysis.minimal_quorums()),
+            merge_fix(analysis_opts.skip_blocking_sets, &|| {
+                analysis.minimal_blocking_sets()
+            }),
+            merge_fix(analysis_opts.skip_splitting_sets, &|| {
+                analysis.minimal_splitting_sets()
+            }),
         );
         (
             top_tier_size,
@@ -513,9 +783,30 @@ fn maybe_load_countries<'a>(nodes_path: &Path, fbas: &'a Fbas) -> Option<Groupin
 
 fn read_csv_from_file(path: &Path) -> Result<Vec<OutputDataPoint>, Box<dyn Error>> {
     let mut reader = Reader::from_path(path)?;
+    let headers = reader.headers()?.clone();
+    let version_index = headers
+        .iter()
+        .position(|header| header == "result_schema_version");
     let mut result = vec![];
-    for line in reader.deserialize() {
-        result.push(line?);
+    for record in reader.records() {
+        let record = record?;
+        let version = version_index
+            .and_then(|i| record.get(i))
+            .and_then(|version| version.parse::<u32>().ok());
+        if version != Some(RESULT_SCHEMA_VERSION) {
+            return Err(Box::new(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Existing results in {:?} have result schema version {}, but this version \
+                    of bulk_fbas_analyzer expects version {}; please do a full re-run instead \
+                    of using `--update`.",
+                    path,
+                    version.map_or_else(|| String::from("<none>"), |v| v.to_string()),
+                    RESULT_SCHEMA_VERSION
+                ),
+            )));
+        }
+        result.push(record.deserialize(Some(&headers))?);
     }
     Ok(result)
 }
@@ -6,15 +6,30 @@ use quicli::prelude::*;
 use structopt::StructOpt;
 
 use itertools::Itertools;
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// Learn things about a given FBAS (parses data from stellarbeat.org)
 #[derive(Debug, StructOpt)]
 struct Cli {
-    /// Path to JSON file describing the FBAS in stellarbeat.org "nodes" format.
+    /// Path to JSON file describing the FBAS in stellarbeat.org "nodes" format, or an http(s) URL
+    /// to fetch it from (requires building with the `url-input` feature).
     /// Will use STDIN if omitted.
     nodes_path: Option<PathBuf>,
 
+    /// Format of the FBAS input; one of "stellarbeat" (stellarbeat.org "nodes" JSON), "internal"
+    /// (ID-based JSON as written by `Fbas::to_internal_json_string`) or "stellar-toml"
+    /// (stellar-core config, not supported yet). If omitted, the format is guessed based on the file extension,
+    /// falling back to "stellarbeat".
+    #[structopt(long = "input-format")]
+    input_format: Option<InputFormat>,
+
     /// Output (and find) minimal quorums.
     #[structopt(short = "q", long = "minimal-quorums")]
     minimal_quorums: bool,
@@ -38,6 +53,25 @@ struct Cli {
     #[structopt(short = "a", long = "all")]
     all: bool,
 
+    /// Only output the top tier and its size, skipping all other analyses. Derives the top tier
+    /// directly from the top tier's common quorum set if the top tier is symmetric, and only falls
+    /// back to finding all minimal quorums if it isn't. Either way, the result is identical to the
+    /// top tier output by the full analysis.
+    #[structopt(
+        long = "top-tier-only",
+        raw(conflicts_with_all = r#"&[
+            "all",
+            "minimal_quorums",
+            "minimal_blocking_sets",
+            "minimal_splitting_sets",
+            "minimal_splitting_sets_with_affected_quorums",
+            "alternative_quorum_intersection_check",
+            "assert_quorum_intersection",
+            "assert_fault_tolerance",
+        ]"#)
+    )]
+    top_tier_only: bool,
+
     /// Use an alternative quorum intersection check that works without enumerating all minimal
     /// quorums. Terminates early if there is no quorum intersection, outputting two
     /// non-intersecting quorums.
@@ -52,16 +86,50 @@ struct Cli {
     #[structopt(short = "d", long = "describe")]
     describe: bool,
 
+    /// Output metrics (see `--describe`) as JSON objects with named fields instead of positional
+    /// arrays.
+    #[structopt(long = "json", requires = "describe")]
+    describe_json: bool,
+
+    /// Output results as stable, diff-friendly `key=value` lines, one metric per line and without
+    /// commentary or durations. Keys are the result names of the default output (e.g.,
+    /// `has_quorum_intersection=true`). Node sets are reported as `<name>.size`, lists of
+    /// quorum sets as `<name>.count` and sets of node sets as `<name>.count` (number of sets),
+    /// `<name>.distinct_nodes`, `<name>.min`, `<name>.max` and `<name>.mean` (set sizes).
+    #[structopt(
+        long = "flat",
+        raw(conflicts_with_all = r#"&[
+            "describe",
+            "describe_json",
+            "output_pretty",
+            "output_pretty_grouped",
+        ]"#)
+    )]
+    flat: bool,
+
     /// In output, identify nodes by their pretty name (depending on specified merge behaviour:
-    /// their public key, organization, ISP or country); default is to use node IDs corresponding
-    /// to indices in the input file.
+    /// their public key, organization, ISP, country or home domain); default is to use node IDs
+    /// corresponding to indices in the input file.
     #[structopt(short = "p", long = "pretty")]
     output_pretty: bool,
 
+    /// When merging by organization, ISP, country or home domain, output the top tier as an
+    /// object mapping each grouping's name to the public keys of its member nodes (nodes that
+    /// belong to no grouping are listed under "ungrouped"), instead of as a flat list of merged
+    /// names.
+    #[structopt(long = "pretty-grouped")]
+    output_pretty_grouped: bool,
+
     /// Silence the commentary about what is what and what it means.
     #[structopt(long = "results-only")]
     results_only: bool,
 
+    /// Unit for reporting analysis durations; one of "s", "ms", "us" or "auto" (picks a
+    /// suitable unit per measurement). Durations in "s" are reported with full precision, all
+    /// others are rounded.
+    #[structopt(long = "duration-unit", default_value = "s")]
+    duration_unit: DurationUnit,
+
     /// Merge nodes by organization - nodes from the same organization are handled as one;
     /// you must provide the path to a stellarbeat.org "organizations" JSON file.
     #[structopt(long = "merge-by-org")]
@@ -77,16 +145,66 @@ struct Cli {
     #[structopt(long = "merge-by-country")]
     ctry_merge: bool,
 
+    /// Merge nodes by home domain - nodes with the same home domain are handled as one;
+    /// The nodes' home domains are extracted from the previously passed JSON file that describes the FBAS.
+    #[structopt(long = "merge-by-home-domain")]
+    home_domain_merge: bool,
+
+    /// When merging, merge the FBAS itself (into an FBAS with one node per grouping) prior to any
+    /// analysis, instead of analyzing the unmerged FBAS and merging the results. Usually much
+    /// faster for large FBASs, but node-level results become unavailable: all results (including
+    /// "nodes_total") refer to merged nodes.
+    #[structopt(long = "merge-first", conflicts_with = "output_pretty_grouped")]
+    merge_first: bool,
+
+    /// Only check the input FBAS for structural problems (references to unknown validators,
+    /// unsatisfiable thresholds, nodes not in their own quorum set, duplicate public keys),
+    /// without running any analyses. Exits with an error if any problems are found.
+    #[structopt(long = "lint")]
+    lint: bool,
+
+    /// After all other analyses, assert that the FBAS enjoys quorum intersection. If it doesn't,
+    /// exit with status code 2 (see also `--assert-fault-tolerance`).
+    #[structopt(long = "assert-quorum-intersection")]
+    assert_quorum_intersection: bool,
+
+    /// After all other analyses, assert that global liveness survives the failure of any N nodes
+    /// (or N groupings, if merging), i.e., that all minimal blocking sets are larger than N. If
+    /// not, exit with status code 4. If several assertions fail, the exit status is the sum
+    /// (bitwise OR) of their status codes.
+    #[structopt(long = "assert-fault-tolerance", value_name = "N")]
+    assert_fault_tolerance: Option<usize>,
+
     /// Prior to any analysis, filter out all nodes marked as `"active" == false` in the input
     /// nodes JSON (the one at `nodes_path`).
     #[structopt(long = "ignore-inactive-nodes")]
     ignore_inactive_nodes: bool,
 
+    /// Prior to any analysis, mark all nodes marked as `"active" == false` in the input nodes JSON
+    /// as crash-faulty instead of filtering them out. Unlike with `--ignore-inactive-nodes`, node
+    /// IDs stay unchanged, i.e., they keep corresponding to node indices in the input file, and
+    /// inactive nodes stay in other nodes' quorum sets as participants that never respond.
+    #[structopt(
+        long = "inactive-nodes-as-faulty",
+        conflicts_with = "ignore_inactive_nodes"
+    )]
+    inactive_nodes_as_faulty: bool,
+
     /// Prior to any analysis, filter out all nodes v for which {v} is a quorum slice (and hence a
     /// quorum).
     #[structopt(long = "ignore-one-node-quorums")]
     ignore_one_node_quorums: bool,
 
+    /// Warn about quorum sets nested deeper than DEPTH levels when loading the FBAS (default: 16).
+    /// Very deeply nested quorum sets can make some analyses run out of stack.
+    #[structopt(long = "max-nesting-depth", value_name = "DEPTH")]
+    max_nesting_depth: Option<usize>,
+
+    /// Refuse to load FBASs with quorum sets nested deeper than allowed by `--max-nesting-depth`,
+    /// instead of just warning about them.
+    #[structopt(long = "reject-deep-quorum-sets")]
+    reject_deep_quorum_sets: bool,
+
     /// Shrink the FBAS to its core nodes prior to analysis, i.e., to the union of all quorum-containing strongly
     /// connected components. Splitting sets analyses will miss any splitting sets that do not
     /// consist entirely of core nodes and don't cause at least one pair of core nodes to end up in
@@ -102,25 +220,47 @@ fn main() -> CliResult {
     let args = Cli::from_args();
     args.verbosity.setup_env_logger("fbas_analyzer")?;
 
+    if args.lint {
+        return lint_and_report(args.nodes_path.as_ref());
+    }
+
     let fbas = load_fbas(
         args.nodes_path.as_ref(),
+        args.input_format,
         args.ignore_inactive_nodes,
+        args.inactive_nodes_as_faulty,
         args.ignore_one_node_quorums,
-    );
-    let (ctry, isp, org) = extract_groupings_todos(&args);
+        nesting_depth_policy(&args),
+    )?;
+    let (ctry, isp, home_domain, org) = extract_groupings_todos(&args);
+    let o_nodes_file = args.nodes_path.as_ref().filter(|path| !is_url(path));
     let groupings = if ctry {
-        maybe_load_countries(args.nodes_path.as_ref(), &fbas)
+        maybe_load_countries(o_nodes_file, &fbas)
     } else if isp {
-        maybe_load_isps(args.nodes_path.as_ref(), &fbas)
+        maybe_load_isps(o_nodes_file, &fbas)
+    } else if home_domain {
+        maybe_load_home_domains(o_nodes_file, &fbas)
     } else if org {
         maybe_load_organizations(args.organizations_path.as_ref(), &fbas)
     } else {
         None
     };
-    let analysis = init_analysis(&fbas, args.only_core_nodes);
+    let merged_fbas = maybe_merge_first(args.merge_first, &groupings);
+    let (fbas, groupings) = if let Some(merged_fbas) = merged_fbas.as_ref() {
+        let merged_groupings = groupings.map(|groups| groups.for_merged_fbas(merged_fbas));
+        (merged_fbas, merged_groupings)
+    } else {
+        (&fbas, groupings)
+    };
+    let analysis = init_analysis(fbas, args.only_core_nodes);
 
     let (q, b, s, big_s) = extract_main_todos(&args);
-    let output = Output::init(&args, &fbas, &groupings);
+    let output = Output::init(&args, fbas, &groupings);
+
+    if args.top_tier_only {
+        find_and_report_top_tier_only(&analysis, &groupings, &output);
+        return Ok(());
+    }
 
     report_overview(&analysis, &groupings, &output);
     output.optional_newline();
@@ -151,40 +291,272 @@ fn main() -> CliResult {
         );
     }
     if q || b {
-        report_top_tier_uncondensed(&analysis, &groupings, &output);
+        report_top_tier_uncondensed(analysis.top_tier(), &groupings, &output);
+    }
+    let exit_code = check_assertions(&args, &analysis, &groupings);
+    if exit_code != 0 {
+        std::process::exit(exit_code);
     }
     Ok(())
 }
 
+const EXIT_CODE_NO_QUORUM_INTERSECTION: i32 = 2;
+const EXIT_CODE_INSUFFICIENT_FAULT_TOLERANCE: i32 = 4;
+
+const STELLAR_TOML_UNSUPPORTED: &str = "Reading stellar-core TOML configs is not supported yet; \
+    please convert your input to stellarbeat.org \"nodes\" JSON.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InputFormat {
+    Stellarbeat,
+    StellarToml,
+    Internal,
+}
+impl InputFormat {
+    fn guess_from_path(o_nodes_path: Option<&PathBuf>) -> Self {
+        match o_nodes_path
+            .and_then(|path| path.extension())
+            .and_then(|extension| extension.to_str())
+        {
+            Some("toml") => InputFormat::StellarToml,
+            _ => InputFormat::Stellarbeat,
+        }
+    }
+}
+impl FromStr for InputFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stellarbeat" => Ok(InputFormat::Stellarbeat),
+            "stellar-toml" => Err(STELLAR_TOML_UNSUPPORTED.to_string()),
+            "internal" => Ok(InputFormat::Internal),
+            _ => Err(format!(
+                "Unknown input format '{}'; expected one of: stellarbeat, stellar-toml, internal",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DurationUnit {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Auto,
+}
+impl DurationUnit {
+    fn format(&self, duration: Duration) -> String {
+        match self {
+            DurationUnit::Seconds => format!("{}s", duration.as_secs_f64()),
+            DurationUnit::Milliseconds => format!("{:.3}ms", duration.as_secs_f64() * 1e3),
+            DurationUnit::Microseconds => format!("{}us", duration.as_micros()),
+            DurationUnit::Auto => {
+                if duration >= Duration::from_secs(1) {
+                    format!("{:.3}s", duration.as_secs_f64())
+                } else if duration >= Duration::from_millis(1) {
+                    format!("{:.3}ms", duration.as_secs_f64() * 1e3)
+                } else {
+                    format!("{}us", duration.as_micros())
+                }
+            }
+        }
+    }
+}
+impl FromStr for DurationUnit {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "s" => Ok(DurationUnit::Seconds),
+            "ms" => Ok(DurationUnit::Milliseconds),
+            "us" => Ok(DurationUnit::Microseconds),
+            "auto" => Ok(DurationUnit::Auto),
+            _ => Err(format!(
+                "Unknown duration unit '{}'; expected one of: s, ms, us, auto",
+                s
+            )),
+        }
+    }
+}
+
+fn nesting_depth_policy(args: &Cli) -> NestingDepthPolicy {
+    let max_depth = args
+        .max_nesting_depth
+        .unwrap_or(QuorumSet::DEFAULT_MAX_NESTING_DEPTH);
+    if args.reject_deep_quorum_sets {
+        NestingDepthPolicy::Reject(max_depth)
+    } else {
+        NestingDepthPolicy::Warn(max_depth)
+    }
+}
 fn load_fbas(
     o_nodes_path: Option<&PathBuf>,
+    o_input_format: Option<InputFormat>,
     ignore_inactive_nodes: bool,
+    inactive_nodes_as_faulty: bool,
     ignore_one_node_quorums: bool,
-) -> Fbas {
-    let mut fbas = if let Some(nodes_path) = o_nodes_path {
+    nesting_depth_policy: NestingDepthPolicy,
+) -> Result<Fbas, Error> {
+    let input_format = o_input_format.unwrap_or_else(|| InputFormat::guess_from_path(o_nodes_path));
+    let mut fbas = match input_format {
+        InputFormat::Stellarbeat => load_stellarbeat_fbas(
+            o_nodes_path,
+            ignore_inactive_nodes,
+            inactive_nodes_as_faulty,
+            nesting_depth_policy,
+        )?,
+        InputFormat::Internal => {
+            if ignore_inactive_nodes || inactive_nodes_as_faulty {
+                return Err(format_err!(
+                    "The internal input format has no notion of inactive nodes; handling \
+                     inactive nodes requires stellarbeat.org \"nodes\" JSON."
+                ));
+            }
+            load_internal_fbas(o_nodes_path, nesting_depth_policy)?
+        }
+        InputFormat::StellarToml => return Err(format_err!("{}", STELLAR_TOML_UNSUPPORTED)),
+    };
+    if ignore_one_node_quorums {
+        fbas = fbas.without_nodes(&fbas.one_node_quorums());
+    }
+    eprintln!("Loaded FBAS with {} nodes.", fbas.number_of_nodes());
+    Ok(fbas)
+}
+fn load_stellarbeat_fbas(
+    o_nodes_path: Option<&PathBuf>,
+    ignore_inactive_nodes: bool,
+    inactive_nodes_as_faulty: bool,
+    nesting_depth_policy: NestingDepthPolicy,
+) -> Result<Fbas, Error> {
+    if let Some(url) = o_nodes_path.filter(|path| is_url(path)) {
+        if ignore_inactive_nodes || inactive_nodes_as_faulty {
+            return Err(format_err!(
+                "Handling inactive nodes is currently not supported when fetching an FBAS from a \
+                 URL; perhaps download it to a file first?"
+            ));
+        }
+        eprintln!("Fetching FBAS JSON from URL...");
+        let fbas = fetch_fbas(url.to_str().unwrap())?;
+        fbas.check_nesting_depth(nesting_depth_policy)?;
+        Ok(fbas)
+    } else if let Some(nodes_path) = o_nodes_path {
         eprintln!("Reading FBAS JSON from file...");
-        let mut fbas = Fbas::from_json_file(nodes_path);
+        let mut fbas = Fbas::from_json_str_with_nesting_depth_policy(
+            &fs::read_to_string(nodes_path)?,
+            nesting_depth_policy,
+        )?;
         if ignore_inactive_nodes {
             let inactive_nodes =
                 FilteredNodes::from_json_file(nodes_path, |v| v["active"] == false);
             fbas = fbas.without_nodes_pretty(&inactive_nodes.into_pretty_vec());
+        } else if inactive_nodes_as_faulty {
+            let inactive_nodes =
+                FilteredNodes::from_json_file(nodes_path, |v| v["active"] == false);
+            fbas.assume_crash_faulty_pretty(&inactive_nodes.into_pretty_vec());
         }
-        fbas
+        Ok(fbas)
     } else {
         eprintln!("Reading FBAS JSON from STDIN...");
-        if ignore_inactive_nodes {
-            panic!(
-                "Ignoring nodes based on additional JSON fields is currently not supported when
-                reading an FBAS from STDIN; perhaps filter the input yourself? (e.g., with `jq`)"
-            );
+        if ignore_inactive_nodes || inactive_nodes_as_faulty {
+            return Err(format_err!(
+                "Ignoring nodes based on additional JSON fields is currently not supported when \
+                 reading an FBAS from STDIN; perhaps filter the input yourself? (e.g., with `jq`)"
+            ));
+        }
+        let mut json = String::new();
+        io::stdin().read_to_string(&mut json)?;
+        Ok(Fbas::from_json_str_with_nesting_depth_policy(
+            &json,
+            nesting_depth_policy,
+        )?)
+    }
+}
+fn load_internal_fbas(
+    o_nodes_path: Option<&PathBuf>,
+    nesting_depth_policy: NestingDepthPolicy,
+) -> Result<Fbas, Error> {
+    if let Some(nodes_path) = o_nodes_path {
+        if is_url(nodes_path) {
+            return Err(format_err!(
+                "Fetching an FBAS from a URL is only supported for stellarbeat.org \"nodes\" JSON."
+            ));
         }
-        Fbas::from_json_stdin()
+        eprintln!("Reading internal FBAS JSON from file...");
+        Ok(Fbas::from_internal_json_str_with_nesting_depth_policy(
+            &fs::read_to_string(nodes_path)?,
+            nesting_depth_policy,
+        )?)
+    } else {
+        eprintln!("Reading internal FBAS JSON from STDIN...");
+        let mut json = String::new();
+        io::stdin().read_to_string(&mut json)?;
+        Ok(Fbas::from_internal_json_str_with_nesting_depth_policy(
+            &json,
+            nesting_depth_policy,
+        )?)
+    }
+}
+fn is_url(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    path.starts_with("http://") || path.starts_with("https://")
+}
+#[cfg(feature = "url-input")]
+fn fetch_fbas(url: &str) -> Result<Fbas, Error> {
+    Ok(Fbas::try_from_json_url(url)?)
+}
+#[cfg(not(feature = "url-input"))]
+fn fetch_fbas(url: &str) -> Result<Fbas, Error> {
+    Err(format_err!(
+        "Can't fetch {}: reading an FBAS from a URL requires building with the `url-input` \
+         feature (e.g., `cargo install fbas_analyzer --features url-input`).",
+        url
+    ))
+}
+fn lint_and_report(o_nodes_path: Option<&PathBuf>) -> CliResult {
+    let lint = if let Some(nodes_path) = o_nodes_path {
+        eprintln!("Reading FBAS JSON from file...");
+        FbasLint::from_json_file(nodes_path)
+            .map_err(|e| format_err!("Error linting {:?}: {}", nodes_path, e))?
+    } else {
+        eprintln!("Reading FBAS JSON from STDIN...");
+        let mut json = String::new();
+        io::stdin().read_to_string(&mut json)?;
+        FbasLint::from_json_str(&json).map_err(|e| format_err!("Error parsing FBAS JSON: {}", e))?
     };
-    if ignore_one_node_quorums {
-        fbas = fbas.without_nodes(&fbas.one_node_quorums());
+    println!("nodes_total: {}", lint.number_of_nodes);
+    println!(
+        "satisfiable_nodes_total: {}",
+        lint.number_of_satisfiable_nodes
+    );
+    for (name, problems) in [
+        (
+            "dangling_references",
+            serde_json::to_string(&lint.dangling_references)?,
+        ),
+        (
+            "unsatisfiable_thresholds",
+            serde_json::to_string(&lint.unsatisfiable_thresholds)?,
+        ),
+        (
+            "nodes_not_in_own_quorum_set",
+            serde_json::to_string(&lint.nodes_not_in_own_quorum_set)?,
+        ),
+        (
+            "duplicate_public_keys",
+            serde_json::to_string(&lint.duplicate_public_keys)?,
+        ),
+    ] {
+        println!("{}: {}", name, problems);
+    }
+    if lint.is_clean() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Found structural problems in the FBAS.",
+        )
+        .into())
     }
-    eprintln!("Loaded FBAS with {} nodes.", fbas.number_of_nodes());
-    fbas
 }
 fn maybe_load_organizations<'a>(
     o_organizations_path: Option<&PathBuf>,
@@ -224,6 +596,39 @@ fn maybe_load_countries<'a>(
         None
     }
 }
+fn maybe_load_home_domains<'a>(
+    o_nodes_path: Option<&PathBuf>,
+    fbas: &'a Fbas,
+) -> Option<Groupings<'a>> {
+    if let Some(nodes_path) = o_nodes_path {
+        eprintln!("Will merge nodes by home domain; reading FBAS JSON from file...");
+        let home_domains = Groupings::home_domains_from_json_file(nodes_path, fbas);
+        eprintln!(
+            "Loaded {} home domains.",
+            home_domains.number_of_groupings()
+        );
+        Some(home_domains)
+    } else {
+        eprintln!("Will not merge. JSON file describing FBAS needed to perform merge.");
+        None
+    }
+}
+fn maybe_merge_first(merge_first: bool, groupings: &Option<Groupings>) -> Option<Fbas> {
+    if !merge_first {
+        None
+    } else if let Some(groups) = groupings {
+        eprintln!("Merging FBAS prior to analysis...");
+        let merged_fbas = groups.merge_fbas();
+        eprintln!(
+            "Merged into an FBAS of {} nodes.",
+            merged_fbas.number_of_nodes()
+        );
+        Some(merged_fbas)
+    } else {
+        eprintln!("Will not merge first, as there is nothing to merge by.");
+        None
+    }
+}
 fn init_analysis(fbas: &Fbas, only_core_nodes: bool) -> Analysis {
     let mut analysis = Analysis::new(fbas);
     if only_core_nodes {
@@ -248,33 +653,42 @@ fn extract_main_todos(args: &Cli) -> (bool, bool, bool, bool) {
         )
     }
 }
-fn extract_groupings_todos(args: &Cli) -> (bool, bool, bool) {
+fn extract_groupings_todos(args: &Cli) -> (bool, bool, bool, bool) {
     if args.ctry_merge {
-        if args.isp_merge || args.organizations_path.is_some() {
+        if args.isp_merge || args.home_domain_merge || args.organizations_path.is_some() {
             eprintln!("Multiple merging options detected; will only merge nodes by country...");
         }
-        (true, false, false)
+        (true, false, false, false)
     } else if args.isp_merge {
-        if args.organizations_path.is_some() {
+        if args.home_domain_merge || args.organizations_path.is_some() {
             eprintln!("Multiple merging options detected; will only merge nodes by ISP...");
         }
-        (false, true, false)
+        (false, true, false, false)
+    } else if args.home_domain_merge {
+        if args.organizations_path.is_some() {
+            eprintln!("Multiple merging options detected; will only merge nodes by home domain...");
+        }
+        (false, false, true, false)
     } else if args.organizations_path.is_some() {
-        (false, false, true)
+        (false, false, false, true)
     } else {
-        (false, false, false)
+        (false, false, false, false)
     }
 }
 
 macro_rules! do_time_and_report {
     ($result_name:expr, $operation:expr, $output:expr) => {{
+        let progress_indicator = $output.start_progress_indicator($result_name);
         let (result, duration) = timed!($operation);
+        progress_indicator.stop();
         $output.timed_result($result_name, result, duration);
     }};
 }
 macro_rules! do_time_maybe_merge_and_report {
     ($result_name:expr, $operation:expr, $groupings:expr, $output:expr) => {{
+        let progress_indicator = $output.start_progress_indicator($result_name);
         let (mut result, duration) = timed!($operation);
+        progress_indicator.stop();
         if let Some(ref groups) = $groupings {
             result = result.merged_by_group(groups).minimal_sets();
         }
@@ -292,6 +706,30 @@ fn report_overview(analysis: &Analysis, groupings: &Option<Groupings>, output: &
         output.optional_comment("(Nodes belonging to the same grouping will be counted as one.)");
     }
 }
+/// Returns the exit code to use, combining the codes of all failed assertions.
+fn check_assertions(args: &Cli, analysis: &Analysis, groupings: &Option<Groupings>) -> i32 {
+    let mut exit_code = 0;
+    if args.assert_quorum_intersection && !analysis.has_quorum_intersection() {
+        eprintln!("Assertion failed: the FBAS lacks quorum intersection.");
+        exit_code |= EXIT_CODE_NO_QUORUM_INTERSECTION;
+    }
+    if let Some(fault_tolerance) = args.assert_fault_tolerance {
+        let mut blocking_sets = analysis.minimal_blocking_sets();
+        if let Some(ref groups) = groupings {
+            blocking_sets = blocking_sets.merged_by_group(groups).minimal_sets();
+        }
+        if blocking_sets.min() <= fault_tolerance {
+            eprintln!(
+                "Assertion failed: the FBAS doesn't tolerate {} failures; its smallest minimal \
+                blocking set has size {}.",
+                fault_tolerance,
+                blocking_sets.min()
+            );
+            exit_code |= EXIT_CODE_INSUFFICIENT_FAULT_TOLERANCE;
+        }
+    }
+    exit_code
+}
 fn check_and_report_if_has_quorum_intersection(
     analysis: &Analysis,
     output: &Output,
@@ -413,16 +851,41 @@ fn find_and_report_minimal_splitting_sets_with_affected_quorums(
     }
     output.keyed_results_uncondensed("minimal_splitting_sets_with_affected_quorums", results);
 }
-fn report_top_tier_uncondensed(
+fn find_and_report_top_tier_only(
     analysis: &Analysis,
     groupings: &Option<Groupings>,
     output: &Output,
 ) {
-    let mut top_tier = analysis.top_tier();
+    let progress_indicator = output.start_progress_indicator("top_tier");
+    let (top_tier, duration) = timed!(analysis
+        .symmetric_top_tier_minimal_quorums()
+        .map(|quorums| NodeIdSetResult::from(quorums.involved_nodes()))
+        .unwrap_or_else(|| analysis.top_tier()));
+    progress_indicator.stop();
+    let top_tier_size = if let Some(ref groups) = groupings {
+        top_tier.merged_by_group(groups).len()
+    } else {
+        top_tier.len()
+    };
+    report_top_tier_uncondensed(top_tier, groupings, output);
+    output.result("top_tier_size", top_tier_size);
+    output.duration("top_tier", duration);
+}
+fn report_top_tier_uncondensed(
+    unmerged_top_tier: NodeIdSetResult,
+    groupings: &Option<Groupings>,
+    output: &Output,
+) {
+    let mut top_tier = unmerged_top_tier.clone();
     if let Some(ref groups) = groupings {
         top_tier = top_tier.merged_by_group(groups);
     }
-    output.result_uncondensed("top_tier", top_tier.clone());
+    match groupings {
+        Some(ref groups) if output.output_pretty_grouped => {
+            output.grouped_result_uncondensed("top_tier", unmerged_top_tier, groups)
+        }
+        _ => output.result_uncondensed("top_tier", top_tier.clone()),
+    }
     output.optional_comment(&format!(
         "\nThere is a total of {} distinct nodes involved in all \
             minimal quorums and minimal blocking sets. \
@@ -435,15 +898,21 @@ fn report_top_tier_uncondensed(
 struct Output<'a> {
     results_only: bool,
     output_pretty: bool,
+    output_pretty_grouped: bool,
     describe: bool,
+    describe_json: bool,
+    flat: bool,
+    show_progress: bool,
+    duration_unit: DurationUnit,
     fbas: &'a Fbas,
     groupings: &'a Option<Groupings<'a>>,
 }
 impl<'a> Output<'a> {
     fn init(args: &Cli, fbas: &'a Fbas, groupings: &'a Option<Groupings>) -> Self {
-        let results_only = args.results_only;
+        let results_only = args.results_only || args.flat;
         let output_pretty = args.output_pretty;
         let describe = args.describe;
+        let show_progress = !results_only && io::stderr().is_terminal();
         if !results_only {
             if !output_pretty {
                 println!(
@@ -451,7 +920,14 @@ impl<'a> Output<'a> {
                     node IDs corresponding to their index in the input file."
                 );
             }
-            if describe {
+            if describe && args.describe_json {
+                println!(
+                    "\"Set of sets\"-type results are described as: \
+                    {{\"count\": #sets, \"distinct_nodes\": #distinct_nodes, \
+                    \"size\": {{\"min\": min_set_size, \"max\": max_set_size, \"mean\": mean_set_size}}, \
+                    \"histogram\": [#sets_with_size_0, #sets_with_size_1, ..., #sets_with_max_set_size]}}"
+                );
+            } else if describe {
                 println!(
                     "\"Set of sets\"-type results are described as: \
                     [#sets, #distinct_nodes, [min_set_size, max_set_size, mean_set_size], \
@@ -462,11 +938,19 @@ impl<'a> Output<'a> {
         Self {
             results_only,
             output_pretty,
+            output_pretty_grouped: args.output_pretty_grouped,
             describe,
+            describe_json: args.describe_json,
+            flat: args.flat,
+            show_progress,
+            duration_unit: args.duration_unit,
             fbas,
             groupings,
         }
     }
+    fn start_progress_indicator(&self, result_name: &str) -> ProgressIndicator {
+        ProgressIndicator::start(result_name, self.show_progress)
+    }
     fn optional_comment(&self, comment: &str) {
         if !self.results_only {
             println!("{}", comment);
@@ -484,23 +968,59 @@ impl<'a> Output<'a> {
         duration: timing::Duration,
     ) {
         self.result(result_name, result);
+        self.duration(result_name, duration);
+    }
+    fn duration(&self, result_name: &str, duration: timing::Duration) {
+        if self.flat {
+            return;
+        }
         println!(
-            "{}_analysis_duration: {}s",
+            "{}_analysis_duration: {}",
             result_name,
-            duration.as_secs_f64()
+            self.duration_unit.format(duration)
         );
     }
     fn result(&self, result_name: &str, result: impl AnalysisResult) {
-        println!("{}: {}", result_name, self.make_string(result));
+        if self.flat {
+            self.flat_result(result_name, result);
+        } else {
+            println!("{}: {}", result_name, self.make_string(result));
+        }
     }
     fn result_uncondensed(&self, result_name: &str, result: impl AnalysisResult) {
-        println!("{}: {}", result_name, self.make_string_uncondensed(result));
+        if self.flat {
+            self.flat_result(result_name, result);
+        } else {
+            println!("{}: {}", result_name, self.make_string_uncondensed(result));
+        }
+    }
+    fn flat_result(&self, result_name: &str, result: impl AnalysisResult) {
+        for (key, value) in result.into_flat_entries(result_name) {
+            println!("{}={}", key, value);
+        }
+    }
+    fn grouped_result_uncondensed(
+        &self,
+        result_name: &str,
+        result: NodeIdSetResult,
+        groupings: &Groupings,
+    ) {
+        println!(
+            "{}: {}",
+            result_name,
+            serde_json::to_string(&result.into_pretty_grouped_node_set(self.fbas, groupings))
+                .expect("Error formatting as JSON")
+        );
     }
     fn keyed_results_uncondensed(
         &self,
         result_name: &str,
         results: impl IntoIterator<Item = (impl AnalysisResult, impl AnalysisResult)>,
     ) {
+        if self.flat {
+            println!("{}.count={}", result_name, results.into_iter().count());
+            return;
+        }
         println!("{}:", result_name);
         for (key, result) in results.into_iter() {
             println!(
@@ -512,7 +1032,9 @@ impl<'a> Output<'a> {
         println!();
     }
     fn make_string(&self, result: impl AnalysisResult) -> String {
-        if self.describe {
+        if self.describe && self.describe_json {
+            result.into_describe_json_string()
+        } else if self.describe {
             result.into_describe_string()
         } else {
             self.make_string_uncondensed(result)
@@ -526,3 +1048,55 @@ impl<'a> Output<'a> {
         }
     }
 }
+
+/// Shows the elapsed time on STDERR for long-running analyses, so that users can tell that the
+/// tool hasn't hung.
+struct ProgressIndicator {
+    stopped: Arc<AtomicBool>,
+    thread_handle: Option<thread::JoinHandle<()>>,
+}
+impl ProgressIndicator {
+    const SILENT_PERIOD: Duration = Duration::from_secs(3);
+    const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
+    fn start(result_name: &str, enabled: bool) -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_handle = if enabled {
+            let stopped = Arc::clone(&stopped);
+            let result_name = result_name.to_string();
+            Some(thread::spawn(move || {
+                let start = Instant::now();
+                let mut printed_something = false;
+                while !stopped.load(Ordering::Relaxed) {
+                    thread::sleep(Self::UPDATE_INTERVAL);
+                    let elapsed = start.elapsed();
+                    if elapsed > Self::SILENT_PERIOD {
+                        eprint!(
+                            "\rStill computing {}... ({}s elapsed)",
+                            result_name,
+                            elapsed.as_secs()
+                        );
+                        io::stderr().flush().ok();
+                        printed_something = true;
+                    }
+                }
+                if printed_something {
+                    // clear line
+                    eprint!("\r\x1b[K");
+                }
+            }))
+        } else {
+            None
+        };
+        Self {
+            stopped,
+            thread_handle,
+        }
+    }
+    fn stop(self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        if let Some(thread_handle) = self.thread_handle {
+            thread_handle.join().ok();
+        }
+    }
+}
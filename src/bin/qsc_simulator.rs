@@ -35,8 +35,9 @@ enum QuorumSetConfiguratorConfig {
     /// Creates threshold=n quorum sets containing all n nodes in the FBAS
     SuperSafe,
     /// Builds quorum sets containing all n nodes in the FBAS, with thresholds chosen such that
-    /// a maximum of f nodes can fail, where (n-1) < (3f+1) <= n
-    Ideal,
+    /// a maximum of f nodes can fail, where (n-1) < (3f+1) <= n.
+    /// If a relative threshold is given, uses it instead.
+    Ideal { relative_threshold: Option<f64> },
     /// Creates random quorum sets of the given size, using 67% thresholds as in "Ideal".
     Random { desired_quorum_set_size: usize },
     /// Creates random quorum sets of the given size and threshold. The probability of picking a
@@ -100,7 +101,13 @@ fn parse_qsc_config(
     use QuorumSetConfiguratorConfig::*;
     match qsc_config {
         SuperSafe => (Rc::new(SuperSafeQsc::new()), 0),
-        Ideal => (Rc::new(IdealQsc::new()), 0),
+        Ideal { relative_threshold } => {
+            if let Some(relative_threshold) = relative_threshold {
+                (Rc::new(RelativeIdealQsc::new(relative_threshold)), 0)
+            } else {
+                (Rc::new(IdealQsc::new()), 0)
+            }
+        }
         Random {
             desired_quorum_set_size,
         } => (Rc::new(RandomQsc::new_simple(desired_quorum_set_size)), 0),
@@ -1,5 +1,6 @@
 use super::*;
 use std::cmp::Ordering;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::mem;
 
@@ -50,6 +51,42 @@ pub struct Fbas {
     pub(crate) nodes: Vec<Node>,
     pub(crate) pk_to_id: HashMap<PublicKey, NodeId>,
 }
+/// How to deal with quorum sets nested deeper than some maximum depth when loading an FBAS; see
+/// `Fbas::check_nesting_depth`. Defaults to warning about quorum sets nested deeper than
+/// `QuorumSet::DEFAULT_MAX_NESTING_DEPTH` levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NestingDepthPolicy {
+    /// Log a warning about quorum sets nested deeper than the given depth, but keep them.
+    Warn(usize),
+    /// Fail with `QuorumSetsTooDeep` if any quorum set is nested deeper than the given depth.
+    Reject(usize),
+}
+impl Default for NestingDepthPolicy {
+    fn default() -> Self {
+        NestingDepthPolicy::Warn(QuorumSet::DEFAULT_MAX_NESTING_DEPTH)
+    }
+}
+
+/// Returned when loading an FBAS with `NestingDepthPolicy::Reject` if some of its nodes have
+/// quorum sets nested deeper than allowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuorumSetsTooDeep {
+    /// Nodes whose quorum sets are nested too deeply.
+    pub nodes: NodeIdSet,
+    pub max_depth: usize,
+}
+impl fmt::Display for QuorumSetsTooDeep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes have quorum sets nested deeper than {} levels.",
+            self.nodes.len(),
+            self.max_depth
+        )
+    }
+}
+impl std::error::Error for QuorumSetsTooDeep {}
+
 impl Fbas {
     /// FBAS of 0 nodes.
     pub fn new() -> Self {
@@ -90,6 +127,25 @@ impl Fbas {
     pub fn get_node_id(&self, public_key: &str) -> Option<NodeId> {
         self.pk_to_id.get(&PublicKey::from(public_key)).copied()
     }
+    /// Public keys of the nodes in `node_set`, sorted by node ID. Unknown node IDs are ignored.
+    pub fn public_keys_of(&self, node_set: &NodeIdSet) -> Vec<PublicKey> {
+        node_set
+            .iter()
+            .filter_map(|node_id| self.nodes.get(node_id))
+            .map(|node| node.public_key.clone())
+            .collect()
+    }
+    /// Inverse of [`Fbas::public_keys_of`]. Unknown public keys are ignored.
+    pub fn node_ids_of(&self, public_keys: &[PublicKey]) -> NodeIdSet {
+        public_keys
+            .iter()
+            .filter_map(|p| self.get_node_id(p))
+            .collect()
+    }
+    /// Like [`Fbas::node_ids_of`], but `None` if any of the public keys is unknown.
+    fn node_ids_of_all(&self, public_keys: &[PublicKey]) -> Option<NodeIdSet> {
+        public_keys.iter().map(|p| self.get_node_id(p)).collect()
+    }
     pub fn get_quorum_set(&self, node_id: NodeId) -> Option<QuorumSet> {
         self.nodes.get(node_id).map(|node| node.quorum_set.clone())
     }
@@ -109,6 +165,122 @@ impl Fbas {
                 .iter()
                 .all(|x| self.nodes[x].quorum_set.is_quorum_slice(node_set))
     }
+    /// Like [`Fbas::is_quorum`], but with nodes referred to by their public keys. Sets containing
+    /// unknown public keys are never quorums.
+    pub fn is_quorum_pretty(&self, nodes: &[PublicKey]) -> bool {
+        self.node_ids_of_all(nodes)
+            .is_some_and(|node_set| self.is_quorum(&node_set))
+    }
+    /// Whether `q1` and `q2` witness a violation of quorum intersection, i.e., whether both are
+    /// quorums and they are disjoint. Useful for auditing externally supplied counterexamples,
+    /// such as the quorums returned by `Analysis::has_quorum_intersection_via_alternative_check`.
+    pub fn is_safety_violation(&self, q1: &NodeIdSet, q2: &NodeIdSet) -> bool {
+        q1.is_disjoint(q2) && self.is_quorum(q1) && self.is_quorum(q2)
+    }
+    /// Like [`Fbas::is_safety_violation`], but with nodes referred to by their public keys. Sets
+    /// containing unknown public keys are never quorums and hence never witness a violation.
+    pub fn is_safety_violation_pretty(&self, q1: &[PublicKey], q2: &[PublicKey]) -> bool {
+        match (self.node_ids_of_all(q1), self.node_ids_of_all(q2)) {
+            (Some(q1), Some(q2)) => self.is_safety_violation(&q1, &q2),
+            _ => false,
+        }
+    }
+    /// Nodes whose quorum sets are nested deeper than `max_depth` levels (see
+    /// `QuorumSet::nesting_depth`). Recursive algorithms might run out of stack on such quorum
+    /// sets; consider removing these nodes (e.g., via `without_nodes`) before analysis.
+    pub fn nodes_with_quorum_sets_deeper_than(&self, max_depth: usize) -> NodeIdSet {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.quorum_set.nesting_depth() > max_depth)
+            .map(|(node_id, _)| node_id)
+            .collect()
+    }
+    /// Checks for quorum sets nested deeper than `policy` allows (see `NestingDepthPolicy`).
+    /// Logs a warning or returns an error, depending on the policy.
+    pub fn check_nesting_depth(&self, policy: NestingDepthPolicy) -> Result<(), QuorumSetsTooDeep> {
+        let (max_depth, reject) = match policy {
+            NestingDepthPolicy::Warn(max_depth) => (max_depth, false),
+            NestingDepthPolicy::Reject(max_depth) => (max_depth, true),
+        };
+        let nodes = self.nodes_with_quorum_sets_deeper_than(max_depth);
+        if nodes.is_empty() {
+            Ok(())
+        } else if reject {
+            Err(QuorumSetsTooDeep { nodes, max_depth })
+        } else {
+            warn!(
+                "{} nodes have quorum sets nested deeper than {} levels; \
+                some analyses might run out of stack!",
+                nodes.len(),
+                max_depth
+            );
+            Ok(())
+        }
+    }
+    pub(crate) fn warn_about_deeply_nested_quorum_sets(&self) {
+        // can't fail as the default policy only warns
+        let _ = self.check_nesting_depth(NestingDepthPolicy::default());
+    }
+    /// Whether `node_set` intersects each of `v`'s quorum slices. As `v` is part of all its
+    /// quorum slices, sets containing `v` are always `v`-blocking.
+    pub fn is_v_blocking_for(&self, v: NodeId, node_set: &NodeIdSet) -> bool {
+        node_set.contains(v) || self.nodes[v].quorum_set.is_v_blocking(node_set)
+    }
+    /// Like [`Fbas::is_v_blocking_for`], but with nodes referred to by their public keys. Unknown
+    /// public keys in `nodes` are ignored; if `v` is unknown, nothing is `v`-blocking.
+    pub fn is_v_blocking_for_pretty(&self, v: &str, nodes: &[PublicKey]) -> bool {
+        self.get_node_id(v)
+            .is_some_and(|v| self.is_v_blocking_for(v, &self.node_ids_of(nodes)))
+    }
+    /// Minimal node sets that intersect each of `v`'s quorum slices, derived from `v`'s quorum
+    /// set. The trivial `v`-blocking set `{v}` is not included (unless it is blocking already due
+    /// to `v`'s quorum set).
+    pub fn minimal_v_blocking_sets(&self, v: NodeId) -> Vec<NodeIdSet> {
+        let quorum_set = &self.nodes[v].quorum_set;
+        let mut sets =
+            remove_non_minimal_node_sets(quorum_set.to_slices(|qset| qset.blocking_threshold()));
+        sets.sort_unstable();
+        sets.sort_by_key(|x| x.len());
+        sets
+    }
+    /// `node_id`'s quorum slices as derived via `QuorumSet::to_quorum_slices`, but with `node_id`
+    /// inserted into each slice, so that they are valid quorum slices for that node. As with
+    /// `QuorumSet::to_quorum_slices`, the returned slices are not necessarily minimal.
+    pub fn node_quorum_slices(&self, node_id: NodeId) -> Vec<NodeIdSet> {
+        let mut slices = self.nodes[node_id].quorum_set.to_quorum_slices();
+        for slice in slices.iter_mut() {
+            slice.insert(node_id);
+        }
+        slices
+    }
+    /// Copy of this FBAS in which `x` was added to the (outer) validators of every quorum set,
+    /// for "what if everyone trusted `x`" comparisons. The threshold policy is as follows:
+    /// - Quorum sets that already list `x` as an outer validator are left unchanged.
+    /// - Unsatisfiable quorum sets (e.g., of crash-faulty or inactive nodes) are left unchanged,
+    ///   so that the corresponding nodes don't become satisfiable by trusting `x`.
+    /// - If `bump_threshold` is `true`, the threshold of every other quorum set is incremented by
+    ///   one; the number of validators and inner quorum sets that may be missing from a slice
+    ///   stays the same. Otherwise thresholds are kept, i.e., `x` can stand in for any other
+    ///   validator or inner quorum set.
+    ///
+    /// Node IDs are unchanged. `x` is also added to its own quorum set (according to the same
+    /// policy).
+    pub fn with_node_added_to_all_quorum_sets(&self, x: NodeId, bump_threshold: bool) -> Fbas {
+        assert!(x < self.number_of_nodes(), "Unknown node ID: {}", x);
+        let mut fbas = self.clone();
+        for node in fbas.nodes.iter_mut() {
+            let quorum_set = &mut node.quorum_set;
+            if quorum_set.validators.contains(&x) || !quorum_set.is_satisfiable() {
+                continue;
+            }
+            quorum_set.validators.push(x);
+            if bump_threshold {
+                quorum_set.threshold += 1;
+            }
+        }
+        fbas
+    }
 }
 impl Hash for Fbas {
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -179,9 +351,259 @@ mod tests {
         assert!(!fbas.is_quorum(&bitset![0]));
     }
 
+    #[test]
+    fn is_quorum_pretty_for_fbas() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            }
+        ]"#,
+        );
+        assert!(fbas.is_quorum_pretty(&[String::from("n0"), String::from("n1")]));
+        assert!(!fbas.is_quorum_pretty(&[String::from("n0"), String::from("n2")]));
+        assert!(!fbas.is_quorum_pretty(&[
+            String::from("n0"),
+            String::from("n1"),
+            String::from("n2")
+        ]));
+    }
+
+    #[test]
+    fn is_safety_violation_pretty_for_fbas() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n0"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 1, "validators": ["n1"] }
+            }
+        ]"#,
+        );
+        let n0 = vec![String::from("n0")];
+        let n1 = vec![String::from("n1")];
+        let n1_and_unknown = vec![String::from("n1"), String::from("n2")];
+        assert!(fbas.is_safety_violation_pretty(&n0, &n1));
+        assert!(!fbas.is_safety_violation_pretty(&n0, &n0));
+        assert!(!fbas.is_safety_violation_pretty(&n0, &n1_and_unknown));
+    }
+
+    #[test]
+    fn is_v_blocking_for_fbas() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+
+        assert!(fbas.is_v_blocking_for(0, &bitset![1, 2]));
+        assert!(fbas.is_v_blocking_for(0, &bitset![0]));
+        assert!(!fbas.is_v_blocking_for(0, &bitset![1]));
+    }
+
+    #[test]
+    fn is_v_blocking_for_pretty_equals_is_v_blocking_for() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let pk = |node_set: &NodeIdSet| fbas.public_keys_of(node_set);
+        let v = &fbas.nodes[0].public_key;
+
+        for node_set in [bitset![1, 2], bitset![0], bitset![1]].iter() {
+            assert_eq!(
+                fbas.is_v_blocking_for(0, node_set),
+                fbas.is_v_blocking_for_pretty(v, &pk(node_set))
+            );
+        }
+        let mut with_unknown = pk(&bitset![1, 2]);
+        with_unknown.push(String::from("unknown"));
+        assert!(fbas.is_v_blocking_for_pretty(v, &with_unknown));
+        assert!(!fbas.is_v_blocking_for_pretty("unknown", &pk(&bitset![0, 1, 2])));
+    }
+
+    #[test]
+    fn minimal_v_blocking_sets_for_flat_quorum_set() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+
+        let expected = bitsetvec![{0, 1}, {0, 2}, {1, 2}];
+        let actual = fbas.minimal_v_blocking_sets(0);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn minimal_v_blocking_sets_for_nested_quorum_set() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": {
+                    "threshold": 2,
+                    "validators": ["n0"],
+                    "innerQuorumSets": [
+                        { "threshold": 2, "validators": ["n1", "n2", "n3"] },
+                        { "threshold": 1, "validators": ["n4", "n1"] }
+                    ]
+                }
+            },
+            { "publicKey": "n1" },
+            { "publicKey": "n2" },
+            { "publicKey": "n3" },
+            { "publicKey": "n4" }
+        ]"#,
+        );
+        // blocking threshold 2 (of 3) on the outer level, 2 (of 3) and 2 (of 2) on the inner levels
+        let expected = bitsetvec![{0, 1, 2}, {0, 1, 3}, {0, 1, 4}, {0, 2, 3}, {1, 2, 4}, {1, 3, 4}];
+        let actual = fbas.minimal_v_blocking_sets(0);
+        assert_eq!(expected, actual);
+        assert!(actual.iter().all(|set| fbas.is_v_blocking_for(0, set)));
+    }
+
+    #[test]
+    fn nodes_with_quorum_sets_deeper_than() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let all_nodes = fbas.all_nodes();
+        assert_eq!(bitset![], fbas.nodes_with_quorum_sets_deeper_than(2));
+        assert_eq!(
+            fbas.nodes_with_quorum_sets_deeper_than(1),
+            all_nodes
+                .iter()
+                .filter(|&id| !fbas.nodes[id].quorum_set.inner_quorum_sets.is_empty())
+                .collect()
+        );
+    }
+
+    #[test]
+    fn check_nesting_depth_warns_or_rejects() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        assert_eq!(
+            Ok(()),
+            fbas.check_nesting_depth(NestingDepthPolicy::Warn(1))
+        );
+        assert_eq!(
+            Ok(()),
+            fbas.check_nesting_depth(NestingDepthPolicy::Reject(2))
+        );
+        assert_eq!(
+            Err(QuorumSetsTooDeep {
+                nodes: fbas.nodes_with_quorum_sets_deeper_than(1),
+                max_depth: 1
+            }),
+            fbas.check_nesting_depth(NestingDepthPolicy::Reject(1))
+        );
+    }
+
     #[test]
     fn empty_set_is_not_quorum() {
         let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
         assert!(!fbas.is_quorum(&bitset![]));
     }
+
+    #[test]
+    fn node_quorum_slices_include_node() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n1", "n2"] }
+            },
+            { "publicKey": "n1" },
+            { "publicKey": "n2" }
+        ]"#,
+        );
+        let expected = bitsetvec![{0, 1}, {0, 2}];
+        let actual = fbas.node_quorum_slices(0);
+        assert_eq!(expected, actual);
+        assert!(actual
+            .iter()
+            .all(|slice| fbas.nodes[0].quorum_set.is_quorum_slice(slice)));
+    }
+
+    #[test]
+    fn node_added_to_all_quorum_sets_with_and_without_threshold_bump() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n3",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n2"] }
+            },
+            {
+                "publicKey": "n4",
+                "quorumSet": { "threshold": 3, "validators": ["n0", "n1"] }
+            }
+        ]"#,
+        );
+        let x = 3;
+
+        let bumped = fbas.with_node_added_to_all_quorum_sets(x, true);
+        let not_bumped = fbas.with_node_added_to_all_quorum_sets(x, false);
+
+        for node_id in 0..4 {
+            assert!(bumped.nodes[node_id].quorum_set.validators.contains(&x));
+            assert!(not_bumped.nodes[node_id].quorum_set.validators.contains(&x));
+            assert_eq!(3, bumped.nodes[node_id].quorum_set.threshold);
+            assert_eq!(2, not_bumped.nodes[node_id].quorum_set.threshold);
+        }
+        // unsatisfiable quorum sets stay as they are
+        assert_eq!(fbas.nodes[4], bumped.nodes[4]);
+        assert_eq!(fbas.nodes[4], not_bumped.nodes[4]);
+
+        // with bumped thresholds, any 3 of the 4 nodes are needed for a quorum
+        let expected = bitsetvec![{0, 1, 2}, {0, 1, 3}, {0, 2, 3}, {1, 2, 3}];
+        let mut actual = find_minimal_quorums(&bumped);
+        actual.sort_unstable();
+        assert_eq!(expected, actual);
+
+        // without, any 2 of them suffice and quorum intersection is lost
+        assert_eq!(6, find_minimal_quorums(&not_bumped).len());
+        assert!(!bumped.is_quorum(&bitset![0, 1]));
+        assert!(not_bumped.is_quorum(&bitset![0, 1]));
+    }
+
+    #[test]
+    fn nonintersecting_quorums_of_broken_fbas_are_safety_violation() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/broken.json"));
+        let analysis = Analysis::new(&fbas);
+        let (_, quorums) = analysis.has_quorum_intersection_via_alternative_check();
+        let quorums = quorums.unwrap().unwrap();
+
+        assert!(fbas.is_safety_violation(&quorums[0], &quorums[1]));
+        assert!(!fbas.is_safety_violation(&quorums[0], &quorums[0]));
+        let not_a_quorum = bitset![quorums[1].iter().next().unwrap()];
+        assert!(!fbas.is_safety_violation(&quorums[0], &not_a_quorum));
+    }
+
+    #[test]
+    fn public_keys_of_and_node_ids_of_round_trip() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let all_nodes = fbas.all_nodes();
+        let public_keys = fbas.public_keys_of(&all_nodes);
+        assert_eq!(fbas.number_of_nodes(), public_keys.len());
+        assert_eq!(all_nodes, fbas.node_ids_of(&public_keys));
+
+        let some_nodes = bitset![2, 3, 5];
+        let public_keys = fbas.public_keys_of(&some_nodes);
+        let expected: Vec<PublicKey> = some_nodes
+            .iter()
+            .map(|id| fbas.nodes[id].public_key.clone())
+            .collect();
+        assert_eq!(expected, public_keys);
+        assert_eq!(some_nodes, fbas.node_ids_of(&public_keys));
+
+        let with_unknown = [public_keys, vec!["unknown".to_string()]].concat();
+        assert_eq!(some_nodes, fbas.node_ids_of(&with_unknown));
+    }
 }
@@ -50,4 +50,12 @@ impl<'fbas> Groupings<'fbas> {
     pub fn number_of_groupings(&self) -> usize {
         self.groupings.len()
     }
+    /// Name and number of member nodes of each grouping, in the order the groupings were read.
+    /// Only nodes that are part of the FBAS are counted.
+    pub fn group_sizes(&self) -> Vec<(String, usize)> {
+        self.groupings
+            .iter()
+            .map(|grouping| (grouping.name.clone(), grouping.validators.len()))
+            .collect()
+    }
 }
@@ -7,7 +7,7 @@ pub use std::collections::HashMap;
 pub use std::collections::HashSet;
 pub use std::collections::VecDeque;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 pub type NodeId = usize; // internal and possibly different between runs
 pub type PublicKey = String;
@@ -1,16 +1,24 @@
 use super::*;
-use itertools::Itertools;
+use itertools::{Either, Itertools};
+use std::cmp;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuorumSet {
     pub threshold: usize,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub validators: Vec<NodeId>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub inner_quorum_sets: Vec<QuorumSet>,
 }
 impl QuorumSet {
+    /// Quorum sets nested deeper than this trigger a warning when loading an FBAS (unless another
+    /// `NestingDepthPolicy` is used). Real-world quorum sets are much shallower; very deep
+    /// nesting can make recursive algorithms (like `is_quorum_slice`) run out of stack.
+    pub const DEFAULT_MAX_NESTING_DEPTH: usize = 16;
+
     pub fn new(
         validators: Vec<NodeId>,
         inner_quorum_sets: Vec<QuorumSet>,
@@ -42,12 +50,61 @@ impl QuorumSet {
             .next()
             .is_some()
     }
+    /// Number of nesting levels, i.e., 1 for a quorum set without inner quorum sets. Computed
+    /// without recursion, so that it is safe to use on arbitrarily deep quorum sets.
+    pub fn nesting_depth(&self) -> usize {
+        let mut max_depth = 0;
+        let mut stack: Vec<(&QuorumSet, usize)> = vec![(self, 1)];
+        while let Some((qset, depth)) = stack.pop() {
+            max_depth = cmp::max(max_depth, depth);
+            stack.extend(
+                qset.inner_quorum_sets
+                    .iter()
+                    .map(|inner| (inner, depth + 1)),
+            );
+        }
+        max_depth
+    }
+    /// Hash of the quorum set's *shape*, i.e., of the thresholds and numbers of validators on all
+    /// nesting levels. Which nodes fill the quorum set, and the order of inner quorum sets, don't
+    /// matter. Grouping nodes by this fingerprint shows how many distinct kinds of configurations
+    /// an FBAS contains.
+    pub fn template_fingerprint(&self) -> u64 {
+        let mut inner_fingerprints: Vec<u64> = self
+            .inner_quorum_sets
+            .iter()
+            .map(|qset| qset.template_fingerprint())
+            .collect();
+        inner_fingerprints.sort_unstable();
+        let mut hasher = DefaultHasher::new();
+        (self.threshold, self.validators.len(), inner_fingerprints).hash(&mut hasher);
+        hasher.finish()
+    }
     pub fn is_satisfiable(&self) -> bool {
         self.validators.len() + self.inner_quorum_sets.len() >= self.threshold
     }
     pub fn is_quorum_slice(&self, node_set: &NodeIdSet) -> bool {
         self.is_slice(node_set, |qset| qset.threshold)
     }
+    /// Whether `node_set` intersects each quorum slice of this quorum set, i.e., whether it
+    /// contains at least "blocking threshold" many validators and/or (recursively) blocked inner
+    /// quorum sets. Unsatisfiable quorum sets are blocked by any node set.
+    pub fn is_v_blocking(&self, node_set: &NodeIdSet) -> bool {
+        if !self.is_satisfiable() {
+            return true;
+        }
+        let found_validator_matches = self
+            .validators
+            .iter()
+            .filter(|x| node_set.contains(**x))
+            .count();
+        let found_inner_quorum_set_matches = self
+            .inner_quorum_sets
+            .iter()
+            .filter(|x| x.is_v_blocking(node_set))
+            .count();
+        found_validator_matches + found_inner_quorum_set_matches >= self.blocking_threshold()
+    }
     /// Each valid quorum slice for this quorum set is a superset (i.e., equal to or a proper superset of)
     /// of at least one of the sets returned by this function. The slices returned here are not
     /// necessarily minimal! Also: The returned slices are not (yet) valid quorum slices for a
@@ -56,6 +113,47 @@ impl QuorumSet {
     pub fn to_quorum_slices(&self) -> Vec<NodeIdSet> {
         self.to_slices(|qset| qset.threshold)
     }
+    /// Lazily enumerates the same (not necessarily minimal) slices as `to_quorum_slices`, in the
+    /// same order. Useful as a building block for custom analyses. Beware that the number of
+    /// slices grows combinatorially with the number of validators and inner quorum sets (e.g.,
+    /// "7 out of 10" already yields 120 slices, deeper nesting multiplies these numbers); see
+    /// also `quorum_slices_up_to`.
+    pub fn quorum_slices_iter(&self) -> impl Iterator<Item = NodeIdSet> + '_ {
+        if self.threshold == 0 {
+            Either::Left(std::iter::once(bitset![]))
+        } else {
+            Either::Right(self.nonempty_slices_iter(|qset| qset.threshold))
+        }
+    }
+    /// The first (at most) `limit` slices yielded by `quorum_slices_iter`, plus whether there
+    /// would have been more. Guards against blowing up on huge quorum sets.
+    pub fn quorum_slices_up_to(&self, limit: usize) -> (Vec<NodeIdSet>, bool) {
+        let mut slices: Vec<NodeIdSet> = self.quorum_slices_iter().take(limit + 1).collect();
+        let hit_limit = slices.len() > limit;
+        slices.truncate(limit);
+        (slices, hit_limit)
+    }
+    /// Equals `to_quorum_slices().len()` but is computed without materializing any slices.
+    pub(crate) fn count_quorum_slices(&self) -> usize {
+        if !self.is_satisfiable() {
+            return 0;
+        }
+        // counts[k]: number of ways to pick slices from k of the validators/inner quorum sets
+        // looked at so far
+        let mut counts = vec![0usize; self.threshold + 1];
+        counts[0] = 1;
+        let subslice_counts = self.validators.iter().map(|_| 1).chain(
+            self.inner_quorum_sets
+                .iter()
+                .map(|qset| qset.count_quorum_slices()),
+        );
+        for subslice_count in subslice_counts {
+            for k in (1..=self.threshold).rev() {
+                counts[k] = counts[k].saturating_add(counts[k - 1].saturating_mul(subslice_count));
+            }
+        }
+        counts[self.threshold]
+    }
     /// Returns some pair of nonintersecting slices if there are any, `None` otherwise.
     pub fn has_nonintersecting_quorum_slices(&self) -> Option<(NodeIdSet, NodeIdSet)> {
         if self.threshold == 0 {
@@ -66,6 +164,19 @@ impl QuorumSet {
             self.has_nonintersecting_quorum_slices_if_no_duplicates()
         }
     }
+    /// Whether `self` equals `other` after renaming all nodes in `self` according to `id_map`.
+    /// The order of validators and inner quorum sets doesn't matter. If `self` contains nodes
+    /// that are not mapped by `id_map`, the quorum sets are not considered equal.
+    pub fn structurally_equal(&self, other: &QuorumSet, id_map: &HashMap<NodeId, NodeId>) -> bool {
+        if let Some(mut renamed_self) = self.renamed(id_map) {
+            let mut other = other.clone();
+            renamed_self.ensure_sorted();
+            other.ensure_sorted();
+            renamed_self == other
+        } else {
+            false
+        }
+    }
     pub(crate) fn is_slice(
         &self,
         node_set: &NodeIdSet,
@@ -116,6 +227,11 @@ impl QuorumSet {
                     })
             })
     }
+    /// Number of validators and/or inner quorum sets that must be blocked for this quorum set to
+    /// be blocked.
+    pub(crate) fn blocking_threshold(&self) -> usize {
+        (self.validators.len() + self.inner_quorum_sets.len() + 1).wrapping_sub(self.threshold)
+    }
     pub(crate) fn contained_nodes_with_duplicates(&self) -> Vec<NodeId> {
         self.validators
             .iter()
@@ -127,6 +243,21 @@ impl QuorumSet {
             )
             .collect()
     }
+    fn renamed(&self, id_map: &HashMap<NodeId, NodeId>) -> Option<Self> {
+        Some(QuorumSet {
+            threshold: self.threshold,
+            validators: self
+                .validators
+                .iter()
+                .map(|node_id| id_map.get(node_id).copied())
+                .collect::<Option<Vec<NodeId>>>()?,
+            inner_quorum_sets: self
+                .inner_quorum_sets
+                .iter()
+                .map(|inner_qset| inner_qset.renamed(id_map))
+                .collect::<Option<Vec<QuorumSet>>>()?,
+        })
+    }
     fn to_subslice_groups<'a>(
         &'a self,
         relevant_threshold: impl Copy + Fn(&QuorumSet) -> usize + 'a,
@@ -264,6 +395,60 @@ mod tests {
         assert!(quorum_set.is_quorum_slice(&bitset![0, 1, 2]));
     }
 
+    #[test]
+    fn is_v_blocking_for_flat_quorum_set() {
+        let quorum_set = flat_qset(&[0, 1, 2, 3], 3);
+        assert!(quorum_set.is_v_blocking(&bitset![0, 1]));
+        assert!(quorum_set.is_v_blocking(&bitset![1, 3, 5]));
+        assert!(!quorum_set.is_v_blocking(&bitset![2, 5]));
+    }
+
+    #[test]
+    fn is_v_blocking_for_nested_quorum_set() {
+        let quorum_set = QuorumSet {
+            threshold: 2,
+            validators: vec![0],
+            inner_quorum_sets: vec![flat_qset(&[1, 2, 3], 2)],
+        };
+        assert!(quorum_set.is_v_blocking(&bitset![0]));
+        assert!(quorum_set.is_v_blocking(&bitset![1, 2]));
+        assert!(!quorum_set.is_v_blocking(&bitset![1]));
+        assert!(!quorum_set.is_v_blocking(&bitset![]));
+    }
+
+    #[test]
+    fn is_v_blocking_for_empty_and_unsatisfiable_quorum_sets() {
+        assert!(!QuorumSet::new_empty().is_v_blocking(&bitset![0, 1]));
+        assert!(QuorumSet::new_unsatisfiable().is_v_blocking(&bitset![]));
+    }
+
+    #[test]
+    fn nesting_depth() {
+        let flat = flat_qset(&[0, 1], 1);
+        let nested = QuorumSet {
+            threshold: 1,
+            validators: vec![],
+            inner_quorum_sets: vec![flat.clone(), QuorumSet::new(vec![], vec![flat.clone()], 1)],
+        };
+        assert_eq!(1, QuorumSet::new_empty().nesting_depth());
+        assert_eq!(1, flat.nesting_depth());
+        assert_eq!(3, nested.nesting_depth());
+    }
+
+    #[test]
+    fn nesting_depth_of_very_deep_quorum_set() {
+        let mut quorum_set = flat_qset(&[0], 1);
+        for _ in 0..100_000 {
+            quorum_set = QuorumSet::new(vec![], vec![quorum_set], 1);
+        }
+        assert_eq!(100_001, quorum_set.nesting_depth());
+        // avoid recursive drop
+        let mut inner_quorum_sets = std::mem::take(&mut quorum_set.inner_quorum_sets);
+        while let Some(mut inner) = inner_quorum_sets.pop() {
+            inner_quorum_sets.append(&mut inner.inner_quorum_sets);
+        }
+    }
+
     #[test]
     fn flat_quorum_set_to_quorum_slices() {
         let quorum_set = flat_qset(&[0, 1, 2], 1);
@@ -371,6 +556,41 @@ mod tests {
             .any(|x| x.is_subset(&miss_problem)));
     }
 
+    #[test]
+    fn quorum_slices_iter_equals_to_quorum_slices() {
+        let quorum_sets = [
+            flat_qset(&[0, 1, 2], 1),
+            flat_qset(&[0, 1, 2, 3], 3),
+            QuorumSet::new_unsatisfiable(),
+            QuorumSet::new_empty(),
+            QuorumSet {
+                threshold: 2,
+                validators: vec![0],
+                inner_quorum_sets: vec![flat_qset(&[1, 2], 1), flat_qset(&[3, 4, 5], 2)],
+            },
+        ];
+        for quorum_set in quorum_sets.iter() {
+            let expected = quorum_set.to_quorum_slices();
+            let actual: Vec<NodeIdSet> = quorum_set.quorum_slices_iter().collect();
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn quorum_slices_up_to_stops_at_limit() {
+        let quorum_set = flat_qset(&[0, 1, 2, 3], 2);
+        let all_slices = quorum_set.to_quorum_slices();
+        assert_eq!(6, all_slices.len());
+
+        let (slices, hit_limit) = quorum_set.quorum_slices_up_to(4);
+        assert_eq!(all_slices[..4].to_vec(), slices);
+        assert!(hit_limit);
+
+        let (slices, hit_limit) = quorum_set.quorum_slices_up_to(6);
+        assert_eq!(all_slices, slices);
+        assert!(!hit_limit);
+    }
+
     #[test]
     fn nested_quorum_set_has_nonintersecting_quorum_slices() {
         let quorum_set = QuorumSet {
@@ -456,4 +676,66 @@ mod tests {
         };
         assert!(!quorum_set.contains_duplicates());
     }
+
+    #[test]
+    fn permuted_quorum_set_is_structurally_equal() {
+        let quorum_set = QuorumSet {
+            threshold: 2,
+            validators: vec![0, 1],
+            inner_quorum_sets: vec![flat_qset(&[2, 3], 1), flat_qset(&[4, 5], 2)],
+        };
+        let permuted = QuorumSet {
+            threshold: 2,
+            validators: vec![14, 15],
+            inner_quorum_sets: vec![flat_qset(&[11, 10], 2), flat_qset(&[13, 12], 1)],
+        };
+        let id_map: HashMap<NodeId, NodeId> = (0..6).map(|id| (id, 15 - id)).collect();
+        assert!(quorum_set.structurally_equal(&permuted, &id_map));
+    }
+
+    #[test]
+    fn differently_structured_quorum_sets_are_not_structurally_equal() {
+        let quorum_set = flat_qset(&[0, 1, 2], 2);
+        let id_map: HashMap<NodeId, NodeId> = (0..3).map(|id| (id, id)).collect();
+        assert!(!quorum_set.structurally_equal(&flat_qset(&[0, 1, 2], 3), &id_map));
+        assert!(!quorum_set.structurally_equal(&flat_qset(&[0, 1, 3], 2), &id_map));
+
+        let incomplete_id_map: HashMap<NodeId, NodeId> = (0..2).map(|id| (id, id)).collect();
+        assert!(!quorum_set.structurally_equal(&quorum_set, &incomplete_id_map));
+    }
+
+    #[test]
+    fn same_shape_quorum_sets_have_equal_template_fingerprints() {
+        let quorum_set = QuorumSet {
+            threshold: 2,
+            validators: vec![0, 1],
+            inner_quorum_sets: vec![flat_qset(&[2, 3], 1), flat_qset(&[4, 5, 6], 2)],
+        };
+        let same_shape = QuorumSet {
+            threshold: 2,
+            validators: vec![7, 8],
+            inner_quorum_sets: vec![flat_qset(&[9, 10, 11], 2), flat_qset(&[0, 1], 1)],
+        };
+        assert_eq!(
+            quorum_set.template_fingerprint(),
+            same_shape.template_fingerprint()
+        );
+    }
+
+    #[test]
+    fn differently_shaped_quorum_sets_have_different_template_fingerprints() {
+        let quorum_set = flat_qset(&[0, 1, 2], 2);
+        assert_ne!(
+            quorum_set.template_fingerprint(),
+            flat_qset(&[0, 1, 2], 3).template_fingerprint()
+        );
+        assert_ne!(
+            quorum_set.template_fingerprint(),
+            flat_qset(&[0, 1, 2, 3], 2).template_fingerprint()
+        );
+        assert_ne!(
+            quorum_set.template_fingerprint(),
+            QuorumSet::new(vec![0, 1], vec![flat_qset(&[2], 1)], 2).template_fingerprint()
+        );
+    }
 }
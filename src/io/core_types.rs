@@ -4,6 +4,14 @@ use std::convert::TryInto;
 
 #[derive(Serialize, Deserialize)]
 pub(crate) struct RawFbas(pub(crate) Vec<RawNode>);
+/// FBAS JSON comes either as a bare array of nodes or, as in some stellarbeat-adjacent exports,
+/// as an object with a `nodes` array (next to, e.g., `organizations`).
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum RawFbasLayout {
+    Bare(Vec<RawNode>),
+    Wrapped { nodes: Vec<RawNode> },
+}
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct RawNode {
@@ -13,6 +21,8 @@ pub(crate) struct RawNode {
     pub(crate) isp: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) geo_data: Option<RawGeoData>,
+    #[serde(alias = "home_domain", skip_serializing_if = "Option::is_none")]
+    pub(crate) home_domain: Option<String>,
 }
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +32,13 @@ pub(crate) struct RawQuorumSet {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub(crate) inner_quorum_sets: Vec<RawQuorumSet>,
 }
+/// Node in the internal, ID-based format (validators are referred to by node IDs).
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InternalNode {
+    pub(crate) public_key: PublicKey,
+    pub(crate) quorum_set: QuorumSet,
+}
 #[serde_as]
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -31,13 +48,94 @@ pub(crate) struct RawGeoData {
     pub(crate) country_name: Option<String>,
 }
 
+/// Returned by [`Fbas::try_from_json_url`].
+#[cfg(feature = "url-input")]
+#[derive(Debug)]
+pub enum FetchFbasError {
+    /// Network failure or non-2xx response, as reported by `ureq`.
+    Http(Box<ureq::Error>),
+    /// The response body isn't valid FBAS JSON.
+    Json(serde_json::Error),
+}
+#[cfg(feature = "url-input")]
+impl fmt::Display for FetchFbasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FetchFbasError::Http(e) => write!(f, "Error fetching FBAS JSON: {}", e),
+            FetchFbasError::Json(e) => write!(f, "Error parsing FBAS JSON: {}", e),
+        }
+    }
+}
+#[cfg(feature = "url-input")]
+impl std::error::Error for FetchFbasError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FetchFbasError::Http(e) => Some(e.as_ref()),
+            FetchFbasError::Json(e) => Some(e),
+        }
+    }
+}
+
 impl Fbas {
+    /// Reads an FBAS from stellarbeat-style JSON: either a bare array of nodes or an object with
+    /// a `nodes` array. In the latter case, other fields (like `organizations`) are ignored here;
+    /// see [`Groupings::organizations_from_json_str`].
     pub fn from_json_str(json: &str) -> Self {
         serde_json::from_str(json).expect("Error parsing FBAS JSON")
     }
     pub fn from_json_file(path: &Path) -> Self {
         Self::from_json_str(&read_or_panic!(path))
     }
+    /// Like [`Fbas::from_json_str`], but deals with deeply nested quorum sets as specified by
+    /// `policy` instead of just warning about them. Still panics on unparsable JSON.
+    pub fn from_json_str_with_nesting_depth_policy(
+        json: &str,
+        policy: NestingDepthPolicy,
+    ) -> Result<Self, QuorumSetsTooDeep> {
+        let raw_nodes = serde_json::from_str::<RawFbasLayout>(json)
+            .expect("Error parsing FBAS JSON")
+            .into_raw_nodes();
+        let fbas = Fbas::from_raw_unchecked(RawFbas(raw_nodes));
+        fbas.check_nesting_depth(policy)?;
+        Ok(fbas)
+    }
+    /// Like [`Fbas::from_json_str`], but never panics, which makes it usable as a fuzzing entry
+    /// point. Input that isn't a JSON array (including invalid UTF-8) yields an empty FBAS; array
+    /// elements that can't be parsed as nodes are skipped, as are nodes with already seen public
+    /// keys.
+    pub fn from_json_bytes_lenient(bytes: &[u8]) -> Self {
+        let values: Vec<serde_json::Value> = serde_json::from_slice(bytes).unwrap_or_default();
+        let mut seen_public_keys = HashSet::new();
+        let raw_nodes = values
+            .into_iter()
+            .filter_map(|value| serde_json::from_value::<RawNode>(value).ok())
+            .filter(|raw_node| seen_public_keys.insert(raw_node.public_key.clone()))
+            .collect();
+        Fbas::from_raw(RawFbas(raw_nodes))
+    }
+    /// Fetches stellarbeat-style FBAS JSON via an HTTP(S) GET request (e.g., from
+    /// `https://api.stellarbeat.io/v1/nodes`) and parses it like [`Fbas::from_json_str`].
+    /// TLS certificates are checked against the bundled Mozilla root certificates. Proxies are
+    /// picked up from the `ALL_PROXY`, `HTTPS_PROXY` or `HTTP_PROXY` environment variables.
+    /// Like the other constructors, panics on network failures, non-2xx responses and
+    /// unparsable JSON; see [`Fbas::try_from_json_url`] for a variant that doesn't. Requires the
+    /// `url-input` feature.
+    #[cfg(feature = "url-input")]
+    pub fn from_json_url(url: &str) -> Self {
+        Self::try_from_json_url(url).unwrap_or_else(|e| panic!("{} (URL: {})", e, url))
+    }
+    /// Like [`Fbas::from_json_url`], but returns network failures, non-2xx responses and
+    /// unparsable JSON as a `FetchFbasError`. Requires the `url-input` feature.
+    #[cfg(feature = "url-input")]
+    pub fn try_from_json_url(url: &str) -> Result<Self, FetchFbasError> {
+        let response = ureq::AgentBuilder::new()
+            .try_proxy_from_env(true)
+            .build()
+            .get(url)
+            .call()
+            .map_err(|e| FetchFbasError::Http(Box::new(e)))?;
+        serde_json::from_reader(response.into_reader()).map_err(FetchFbasError::Json)
+    }
     pub fn from_json_stdin() -> Self {
         serde_json::from_reader(io::stdin()).expect("Error reading FBAS JSON from STDIN")
     }
@@ -47,7 +145,67 @@ impl Fbas {
     pub fn to_json_string_pretty(&self) -> String {
         serde_json::to_string_pretty(&self).expect("Error converting FBAS to pretty JSON!")
     }
+    /// Reads an FBAS in the internal, ID-based format, as written by
+    /// [`Fbas::to_internal_json_string`]. Quorum sets refer to validators by node ID (i.e., by
+    /// their index in the list of nodes) instead of by public key.
+    pub fn from_internal_json_str(json: &str) -> Self {
+        let fbas = Self::from_internal_json_str_unchecked(json);
+        fbas.warn_about_deeply_nested_quorum_sets();
+        fbas
+    }
+    /// Like [`Fbas::from_internal_json_str`], but deals with deeply nested quorum sets as
+    /// specified by `policy` instead of just warning about them.
+    pub fn from_internal_json_str_with_nesting_depth_policy(
+        json: &str,
+        policy: NestingDepthPolicy,
+    ) -> Result<Self, QuorumSetsTooDeep> {
+        let fbas = Self::from_internal_json_str_unchecked(json);
+        fbas.check_nesting_depth(policy)?;
+        Ok(fbas)
+    }
+    fn from_internal_json_str_unchecked(json: &str) -> Self {
+        let internal_nodes: Vec<InternalNode> =
+            serde_json::from_str(json).expect("Error parsing internal FBAS JSON");
+        let n = internal_nodes.len();
+        let mut fbas = Fbas::new();
+        for internal_node in internal_nodes.into_iter() {
+            assert!(
+                internal_node
+                    .quorum_set
+                    .contained_nodes()
+                    .iter()
+                    .all(|id| id < n),
+                "Quorum set of node {} refers to nonexistent node IDs",
+                internal_node.public_key
+            );
+            fbas.add_node(Node {
+                public_key: internal_node.public_key,
+                quorum_set: internal_node.quorum_set,
+            });
+        }
+        fbas
+    }
+    pub fn from_internal_json_file(path: &Path) -> Self {
+        Self::from_internal_json_str(&read_or_panic!(path))
+    }
+    /// Writes the FBAS in the internal, ID-based format; see [`Fbas::from_internal_json_str`].
+    pub fn to_internal_json_string(&self) -> String {
+        let internal_nodes: Vec<InternalNode> = self
+            .nodes
+            .iter()
+            .map(|node| InternalNode {
+                public_key: node.public_key.clone(),
+                quorum_set: node.quorum_set.clone(),
+            })
+            .collect();
+        serde_json::to_string(&internal_nodes).expect("Error converting FBAS to internal JSON!")
+    }
     pub(crate) fn from_raw(raw_fbas: RawFbas) -> Self {
+        let fbas = Self::from_raw_unchecked(raw_fbas);
+        fbas.warn_about_deeply_nested_quorum_sets();
+        fbas
+    }
+    fn from_raw_unchecked(raw_fbas: RawFbas) -> Self {
         let raw_nodes: Vec<RawNode> = raw_fbas.0.into_iter().collect();
 
         let pk_to_id: HashMap<PublicKey, NodeId> = raw_nodes
@@ -85,8 +243,16 @@ impl<'de> Deserialize<'de> for Fbas {
     where
         D: Deserializer<'de>,
     {
-        let raw_fbas = RawFbas::deserialize(deserializer)?;
-        Ok(Fbas::from_raw(raw_fbas))
+        let raw_nodes = RawFbasLayout::deserialize(deserializer)?.into_raw_nodes();
+        Ok(Fbas::from_raw(RawFbas(raw_nodes)))
+    }
+}
+impl RawFbasLayout {
+    pub(crate) fn into_raw_nodes(self) -> Vec<RawNode> {
+        match self {
+            RawFbasLayout::Bare(raw_nodes) => raw_nodes,
+            RawFbasLayout::Wrapped { nodes } => nodes,
+        }
     }
 }
 impl Node {
@@ -107,6 +273,7 @@ impl Node {
             quorum_set: Some(self.quorum_set.to_raw(fbas)),
             isp: None,
             geo_data: None,
+            home_domain: None,
         }
     }
 }
@@ -304,6 +471,69 @@ mod tests {
         assert_eq!(expected_quorum_sets, actual_quorum_sets);
     }
 
+    #[test]
+    fn from_json_accepts_object_with_nodes_array() {
+        let bare = r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+            }]"#;
+        let wrapped = r#"{
+            "nodes": [
+                {
+                    "publicKey": "n0",
+                    "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+                },
+                {
+                    "publicKey": "n1",
+                    "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] }
+                }
+            ],
+            "organizations": [
+                { "name": "Org", "validators": ["n0", "n1"] }
+            ]}"#;
+
+        assert_eq!(Fbas::from_json_str(bare), Fbas::from_json_str(wrapped));
+    }
+
+    #[test]
+    fn from_json_bytes_lenient_doesnt_panic_on_garbage() {
+        let inputs: Vec<&[u8]> = vec![
+            b"",
+            b"\xff\xfe\x00garbage",
+            b"{\"publicKey\": \"n0\"}",
+            b"[1, null, {}, [\"n0\"]",
+            br#"[{"publicKey": "n0", "quorumSet": {"threshold": -1, "validators": "n0"}}]"#,
+        ];
+        for input in inputs.into_iter() {
+            assert_eq!(0, Fbas::from_json_bytes_lenient(input).number_of_nodes());
+        }
+    }
+
+    #[test]
+    fn from_json_bytes_lenient_skips_broken_and_duplicate_nodes() {
+        let input = br#"[
+            { "publicKey": "n0", "quorumSet": null },
+            { "publicKey": "n1", "quorumSet": { "threshold": 1, "validators": ["n0"] } },
+            { "quorumSet": { "threshold": 1, "validators": ["n0"] } },
+            42,
+            { "publicKey": "n0", "quorumSet": { "threshold": 1, "validators": ["n1"] } }
+        ]"#;
+        let fbas = Fbas::from_json_bytes_lenient(input);
+
+        assert_eq!(2, fbas.number_of_nodes());
+        assert_eq!(Some(0), fbas.get_node_id("n0"));
+        assert_eq!(Some(QuorumSet::new_unsatisfiable()), fbas.get_quorum_set(0));
+        assert_eq!(
+            Some(QuorumSet::new(vec![0], vec![], 1)),
+            fbas.get_quorum_set(1)
+        );
+    }
+
     #[test]
     fn to_json_and_back_results_in_identical_fbas() {
         let original = Fbas::new_generic_unconfigured(7);
@@ -329,6 +559,99 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn from_internal_json_to_fbas() {
+        let input = r#"[
+            { "publicKey": "n0", "quorumSet": { "threshold": 2, "validators": [0, 1] } },
+            { "publicKey": "n1", "quorumSet": { "threshold": 1, "innerQuorumSets": [
+                { "threshold": 1, "validators": [0] }
+            ] } },
+            { "publicKey": "n2", "quorumSet": { "threshold": 1 } }
+        ]"#;
+        let expected = Fbas::from_json_str(
+            r#"[
+            { "publicKey": "n0", "quorumSet": { "threshold": 2, "validators": ["n0", "n1"] } },
+            { "publicKey": "n1", "quorumSet": { "threshold": 1, "validators": [], "innerQuorumSets": [
+                { "threshold": 1, "validators": ["n0"] }
+            ] } },
+            { "publicKey": "n2" }
+        ]"#,
+        );
+        let actual = Fbas::from_internal_json_str(input);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn internal_json_round_trip() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let actual = Fbas::from_internal_json_str(&fbas.to_internal_json_string());
+        assert_eq!(fbas, actual);
+        assert_eq!(
+            fbas.get_node_id("GCM6QMP3DLRPTAZW2UZPCPX2LF3SXWXKPMP3GKFZBDSF3QZGV2G5QSTK"),
+            actual.get_node_id("GCM6QMP3DLRPTAZW2UZPCPX2LF3SXWXKPMP3GKFZBDSF3QZGV2G5QSTK")
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_internal_json_panics_on_nonexistent_node_ids() {
+        Fbas::from_internal_json_str(
+            r#"[{ "publicKey": "n0", "quorumSet": { "threshold": 1, "validators": [1] } }]"#,
+        );
+    }
+
+    #[test]
+    fn from_json_with_nesting_depth_policy_warns_or_rejects() {
+        let input = r#"[
+            { "publicKey": "n0", "quorumSet": { "threshold": 1, "validators": ["n1"] } },
+            { "publicKey": "n1", "quorumSet": { "threshold": 1, "validators": [], "innerQuorumSets": [
+                { "threshold": 1, "validators": [], "innerQuorumSets": [
+                    { "threshold": 1, "validators": ["n0"] }
+                ] }
+            ] } }
+        ]"#;
+        let expected = Fbas::from_json_str(input);
+
+        let actual =
+            Fbas::from_json_str_with_nesting_depth_policy(input, NestingDepthPolicy::Warn(2));
+        assert_eq!(Ok(expected.clone()), actual);
+
+        let actual =
+            Fbas::from_json_str_with_nesting_depth_policy(input, NestingDepthPolicy::Reject(3));
+        assert_eq!(Ok(expected), actual);
+
+        let actual =
+            Fbas::from_json_str_with_nesting_depth_policy(input, NestingDepthPolicy::Reject(2));
+        assert_eq!(
+            Err(QuorumSetsTooDeep {
+                nodes: bitset![1],
+                max_depth: 2
+            }),
+            actual
+        );
+    }
+
+    #[test]
+    fn from_internal_json_with_nesting_depth_policy_warns_or_rejects() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let json = fbas.to_internal_json_string();
+
+        let actual = Fbas::from_internal_json_str_with_nesting_depth_policy(
+            &json,
+            NestingDepthPolicy::Warn(1),
+        );
+        assert_eq!(Ok(fbas.clone()), actual);
+
+        let actual = Fbas::from_internal_json_str_with_nesting_depth_policy(
+            &json,
+            NestingDepthPolicy::Reject(1),
+        );
+        assert_eq!(
+            Some(fbas.nodes_with_quorum_sets_deeper_than(1)),
+            actual.err().map(|error| error.nodes)
+        );
+    }
+
     // broken since we also have "organizations" test files now
     // #[test]
     // fn from_json_doesnt_panic_for_test_files() {
@@ -338,4 +661,86 @@ mod tests {
     //         Fbas::from_json_file(path.to_str().unwrap());
     //     }
     // }
+
+    /// Serves a single HTTP request on localhost with the given status line and body.
+    #[cfg(feature = "url-input")]
+    fn serve_once(status: &'static str, body: String) -> (String, std::thread::JoinHandle<()>) {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/nodes", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        (url, server)
+    }
+
+    #[cfg(feature = "url-input")]
+    #[test]
+    fn from_json_url_fetches_and_parses_fbas() {
+        let body = fs::read_to_string("test_data/correct_trivial.json").unwrap();
+        let (url, server) = serve_once("200 OK", body.clone());
+        let actual = Fbas::from_json_url(&url);
+        server.join().unwrap();
+
+        assert_eq!(Fbas::from_json_str(&body), actual);
+    }
+
+    #[cfg(feature = "url-input")]
+    #[test]
+    #[should_panic(expected = "Error fetching FBAS JSON")]
+    fn from_json_url_panics_on_network_errors() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/nodes", listener.local_addr().unwrap());
+        drop(listener);
+        Fbas::from_json_url(&url);
+    }
+
+    #[cfg(feature = "url-input")]
+    #[test]
+    fn try_from_json_url_returns_network_errors() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/nodes", listener.local_addr().unwrap());
+        drop(listener);
+        assert!(matches!(
+            Fbas::try_from_json_url(&url),
+            Err(FetchFbasError::Http(_))
+        ));
+    }
+
+    #[cfg(feature = "url-input")]
+    #[test]
+    fn try_from_json_url_returns_non_2xx_responses_as_errors() {
+        let (url, server) = serve_once("404 Not Found", "nope".to_string());
+        let result = Fbas::try_from_json_url(&url);
+        server.join().unwrap();
+
+        assert!(
+            matches!(result, Err(FetchFbasError::Http(e)) if e.kind() == ureq::ErrorKind::HTTP)
+        );
+    }
+
+    #[cfg(feature = "url-input")]
+    #[test]
+    fn try_from_json_url_returns_unparsable_json_as_errors() {
+        let (url, server) = serve_once("200 OK", "[{\"publicKey\": 1}]".to_string());
+        let result = Fbas::try_from_json_url(&url);
+        server.join().unwrap();
+
+        assert!(matches!(result, Err(FetchFbasError::Json(_))));
+    }
 }
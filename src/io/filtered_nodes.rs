@@ -30,16 +30,22 @@ impl FilteredNodes {
         P: FnMut(&Value) -> bool,
     {
         let mut nodes = vec![];
-        if let Ok(Value::Array(values)) = serde_json::from_str::<Value>(json) {
-            for value in values.into_iter() {
-                if predicate(&value) {
-                    nodes.push(
-                        value["publicKey"]
-                            .as_str()
-                            .expect("Node without publicKey!")
-                            .into(),
-                    );
-                }
+        let values = match serde_json::from_str::<Value>(json) {
+            Ok(Value::Array(values)) => values,
+            Ok(Value::Object(mut object)) => match object.remove("nodes") {
+                Some(Value::Array(values)) => values,
+                _ => vec![],
+            },
+            _ => vec![],
+        };
+        for value in values.into_iter() {
+            if predicate(&value) {
+                nodes.push(
+                    value["publicKey"]
+                        .as_str()
+                        .expect("Node without publicKey!")
+                        .into(),
+                );
             }
         }
         Self(nodes)
@@ -2,6 +2,16 @@ use super::*;
 
 #[derive(Serialize, Deserialize)]
 struct RawGroupings(Vec<RawGrouping>);
+/// Organizations JSON comes either as a bare array or embedded in an FBAS JSON object, next to
+/// its `nodes` array. Some sources instead provide an object mapping organization names to the
+/// public keys of their validators.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawOrganizationsLayout {
+    Bare(RawGroupings),
+    Wrapped { organizations: RawGroupings },
+    ByName(BTreeMap<String, Vec<PublicKey>>),
+}
 #[derive(Serialize, Deserialize)]
 struct RawGrouping {
     name: String,
@@ -14,24 +24,47 @@ impl<'fbas> Groupings<'fbas> {
             fbas,
         )
     }
+    /// Reads organizations from stellarbeat-style JSON: either a bare array of organizations or
+    /// an FBAS JSON object with an `organizations` array (next to its `nodes`). Also accepts an
+    /// object mapping organization names to arrays of public keys, like
+    /// `{"OrgName": ["pk1", "pk2"]}`; organizations are then sorted by name.
     pub fn organizations_from_json_str(orgs_json: &str, fbas: &'fbas Fbas) -> Self {
-        Self::from_raw(
-            serde_json::from_str(orgs_json).expect("Error parsing Organizations JSON"),
-            fbas,
-        )
+        let raw_groupings =
+            match serde_json::from_str(orgs_json).expect("Error parsing Organizations JSON") {
+                RawOrganizationsLayout::Bare(raw_groupings) => raw_groupings,
+                RawOrganizationsLayout::Wrapped { organizations } => organizations,
+                RawOrganizationsLayout::ByName(validators_by_name) => RawGroupings(
+                    validators_by_name
+                        .into_iter()
+                        .map(|(name, validators)| RawGrouping { name, validators })
+                        .collect(),
+                ),
+            };
+        Self::from_raw(raw_groupings, fbas)
     }
     pub fn isps_from_json_str(nodes_json: &str, fbas: &'fbas Fbas) -> Self {
-        let raw_nodes: Vec<RawNode> =
-            serde_json::from_str(nodes_json).expect("Error parsing FBAS JSON");
+        let raw_nodes = serde_json::from_str::<RawFbasLayout>(nodes_json)
+            .expect("Error parsing FBAS JSON")
+            .into_raw_nodes();
         let raw_groupings = RawGroupings::isps_from_raw_nodes(raw_nodes);
         Groupings::from_raw(raw_groupings, fbas)
     }
     pub fn countries_from_json_str(nodes_json: &str, fbas: &'fbas Fbas) -> Self {
-        let raw_nodes: Vec<RawNode> =
-            serde_json::from_str(nodes_json).expect("Error parsing FBAS JSON");
+        let raw_nodes = serde_json::from_str::<RawFbasLayout>(nodes_json)
+            .expect("Error parsing FBAS JSON")
+            .into_raw_nodes();
         let raw_groupings = RawGroupings::countries_from_raw_nodes(raw_nodes);
         Groupings::from_raw(raw_groupings, fbas)
     }
+    /// Groups nodes by their home domain, i.e., the `homeDomain` (or `home_domain`) field in
+    /// stellarbeat-style nodes JSON. This is often more fine-grained than organizations.
+    pub fn home_domains_from_json_str(nodes_json: &str, fbas: &'fbas Fbas) -> Self {
+        let raw_nodes = serde_json::from_str::<RawFbasLayout>(nodes_json)
+            .expect("Error parsing FBAS JSON")
+            .into_raw_nodes();
+        let raw_groupings = RawGroupings::home_domains_from_raw_nodes(raw_nodes);
+        Groupings::from_raw(raw_groupings, fbas)
+    }
     pub fn from_json_file(path: &Path, fbas: &'fbas Fbas) -> Self {
         Self::from_json_str(&read_or_panic!(path), fbas)
     }
@@ -44,6 +77,9 @@ impl<'fbas> Groupings<'fbas> {
     pub fn countries_from_json_file(path: &Path, fbas: &'fbas Fbas) -> Self {
         Self::countries_from_json_str(&read_or_panic!(path), fbas)
     }
+    pub fn home_domains_from_json_file(path: &Path, fbas: &'fbas Fbas) -> Self {
+        Self::home_domains_from_json_str(&read_or_panic!(path), fbas)
+    }
     fn from_raw(raw_groupings: RawGroupings, fbas: &'fbas Fbas) -> Self {
         let groupings: Vec<Grouping> = raw_groupings
             .0
@@ -96,72 +132,41 @@ impl Grouping {
 
 impl RawGroupings {
     fn isps_from_raw_nodes(raw_nodes: Vec<RawNode>) -> Self {
-        let mut isp_to_validators: HashMap<String, Vec<PublicKey>> =
-            HashMap::with_capacity(raw_nodes.len());
-        let mut raw_groupings: Vec<RawGrouping> = Vec::with_capacity(isp_to_validators.len());
-        for raw_node in &raw_nodes {
-            if let Some(name) = &raw_node.isp {
-                let mut isp = name.clone();
-                isp = remove_special_chars_from_grouping_name(isp);
-                if isp_to_validators.get(&isp).is_none() {
-                    isp_to_validators.insert(isp.clone(), Vec::new());
-                }
-                isp_to_validators
-                    .get_mut(&isp)
-                    .unwrap()
-                    .push(raw_node.public_key.clone());
-            };
-        }
-        let mut grouping_names = Vec::with_capacity(isp_to_validators.len());
-        for key in isp_to_validators.keys() {
-            grouping_names.push(key);
-        }
-        grouping_names.sort_unstable();
-        for name in grouping_names {
-            if let Some(validators) = isp_to_validators.get(name) {
-                let raw_grouping = RawGrouping {
-                    name: name.clone(),
-                    validators: validators.clone(),
-                };
-                raw_groupings.push(raw_grouping);
-            }
-        }
-        RawGroupings(raw_groupings)
+        Self::from_raw_nodes_grouped_by(raw_nodes, |raw_node| raw_node.isp.as_ref())
     }
     fn countries_from_raw_nodes(raw_nodes: Vec<RawNode>) -> Self {
-        let mut country_to_validators: HashMap<String, Vec<PublicKey>> =
-            HashMap::with_capacity(raw_nodes.len());
-        let mut raw_groupings: Vec<RawGrouping> = Vec::with_capacity(country_to_validators.len());
+        Self::from_raw_nodes_grouped_by(raw_nodes, |raw_node| {
+            raw_node
+                .geo_data
+                .as_ref()
+                .and_then(|geo_data| geo_data.country_name.as_ref())
+        })
+    }
+    fn home_domains_from_raw_nodes(raw_nodes: Vec<RawNode>) -> Self {
+        Self::from_raw_nodes_grouped_by(raw_nodes, |raw_node| raw_node.home_domain.as_ref())
+    }
+    /// Groups nodes by the (cleaned up) grouping name returned by `grouping_name`; nodes for which
+    /// it returns `None` are not part of any grouping. Groupings are sorted by name.
+    fn from_raw_nodes_grouped_by(
+        raw_nodes: Vec<RawNode>,
+        grouping_name: impl Fn(&RawNode) -> Option<&String>,
+    ) -> Self {
+        let mut name_to_validators: BTreeMap<String, Vec<PublicKey>> = BTreeMap::new();
         for raw_node in &raw_nodes {
-            if let Some(geodata) = &raw_node.geo_data {
-                if let Some(name) = &geodata.country_name {
-                    let mut country = name.clone();
-                    country = remove_special_chars_from_grouping_name(country);
-                    if country_to_validators.get(&country.clone()).is_none() {
-                        country_to_validators.insert(country.clone(), Vec::new());
-                    }
-                    country_to_validators
-                        .get_mut(&country.clone())
-                        .unwrap()
-                        .push(raw_node.public_key.clone());
-                }
-            };
-        }
-        let mut grouping_names = Vec::with_capacity(country_to_validators.len());
-        for key in country_to_validators.keys() {
-            grouping_names.push(key);
-        }
-        grouping_names.sort_unstable();
-        for name in grouping_names {
-            if let Some(validators) = country_to_validators.get(name) {
-                let raw_grouping = RawGrouping {
-                    name: name.clone(),
-                    validators: validators.clone(),
-                };
-                raw_groupings.push(raw_grouping);
+            if let Some(name) = grouping_name(raw_node) {
+                let name = remove_special_chars_from_grouping_name(name.clone());
+                name_to_validators
+                    .entry(name)
+                    .or_default()
+                    .push(raw_node.public_key.clone());
             }
         }
-        RawGroupings(raw_groupings)
+        RawGroupings(
+            name_to_validators
+                .into_iter()
+                .map(|(name, validators)| RawGrouping { name, validators })
+                .collect(),
+        )
     }
 }
 
@@ -213,6 +218,71 @@ mod tests {
         assert_eq!(expected_validators, actual_validators);
     }
     #[test]
+    fn group_sizes_count_member_nodes() {
+        let json = r#"[
+            {
+                "publicKey": "GCGB2",
+                "isp": "Google.com"
+            },
+            {
+                "publicKey": "GCM6Q",
+                "isp": "Hetzner"
+            },
+            {
+                "publicKey": "GABMK",
+                "isp": "Google.com"
+            }]"#;
+        let fbas = Fbas::from_json_str(json);
+        let isps = Groupings::isps_from_json_str(json, &fbas);
+        let expected = vec![
+            (String::from("Google.com"), 2),
+            (String::from("Hetzner"), 1),
+        ];
+        assert_eq!(expected, isps.group_sizes());
+    }
+    #[test]
+    fn read_home_domains_from_nodes_json_str() {
+        let json = r#"[
+            {
+                "publicKey": "GCGB2",
+                "homeDomain": "stellar.org"
+            },
+            {
+                "publicKey": "GCM6Q",
+                "homeDomain": "lobstr.co"
+            },
+            {
+                "publicKey": "GCHAR",
+                "home_domain": "stellar.org"
+            },
+            {
+                "publicKey": "GABMK"
+            }]"#;
+        let fbas = Fbas::from_json_str(json);
+        let home_domains = Groupings::home_domains_from_json_str(json, &fbas);
+        let expected_names = vec!["lobstr.co", "stellar.org"];
+        let actual_names: Vec<String> = home_domains
+            .groupings
+            .iter()
+            .map(|x| x.name.clone())
+            .collect();
+        let expected_validators: Vec<Vec<NodeId>> = vec![vec![1], vec![0, 2]];
+        let actual_validators: Vec<Vec<NodeId>> = home_domains
+            .groupings
+            .iter()
+            .map(|x| x.validators.clone())
+            .collect();
+        assert_eq!(expected_names, actual_names);
+        assert_eq!(expected_validators, actual_validators);
+    }
+    #[test]
+    fn read_home_domains_from_stellarbeat_nodes_json_file() {
+        let path = Path::new("test_data/stellarbeat_nodes_2019-09-17.json");
+        let fbas = Fbas::from_json_file(path);
+        let home_domains = Groupings::home_domains_from_json_file(path, &fbas);
+        assert_eq!(23, home_domains.number_of_groupings());
+    }
+    #[test]
     fn read_countries_from_nodes_json_str() {
         let json = r#"[
             {
@@ -374,6 +444,87 @@ mod tests {
         assert_eq!(groupings, organizations);
     }
     #[test]
+    fn organizations_from_json_str_accepts_object_with_organizations_array() {
+        let wrapped = r#"{
+            "nodes": [
+                {
+                    "publicKey": "Jim",
+                    "isp": "Hetzner"
+                },
+                {
+                    "publicKey": "Jon",
+                    "isp": "Hetzner"
+                },
+                {
+                    "publicKey": "Bob"
+                }
+            ],
+            "organizations": [
+                {
+                    "name": "J Mafia",
+                    "validators": [ "Jim", "Jon" ]
+                }
+            ]}"#;
+        let fbas = Fbas::from_json_str(wrapped);
+        let expected = Groupings::organizations_from_json_str(
+            r#"[
+            {
+                "name": "J Mafia",
+                "validators": [ "Jim", "Jon" ]
+            }
+            ]"#,
+            &fbas,
+        );
+        let actual = Groupings::organizations_from_json_str(wrapped, &fbas);
+        assert_eq!(expected, actual);
+        assert_eq!(
+            vec!["Hetzner"],
+            Groupings::isps_from_json_str(wrapped, &fbas)
+                .groupings
+                .into_iter()
+                .map(|isp| isp.name)
+                .collect::<Vec<String>>()
+        );
+    }
+    #[test]
+    fn organizations_can_be_read_from_map_by_name() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "Jim"
+            },
+            {
+                "publicKey": "Jon"
+            },
+            {
+                "publicKey": "Bob"
+            }
+            ]"#,
+        );
+        let expected = Groupings::organizations_from_json_str(
+            r#"[
+            {
+                "name": "B Mafia",
+                "validators": [ "Bob" ]
+            },
+            {
+                "name": "J Mafia",
+                "validators": [ "Jim", "Jon" ]
+            }
+            ]"#,
+            &fbas,
+        );
+        let actual = Groupings::organizations_from_json_str(
+            r#"{
+                "J Mafia": [ "Jim", "Jon" ],
+                "B Mafia": [ "Bob" ]
+            }"#,
+            &fbas,
+        );
+        assert_eq!(expected, actual);
+        assert_eq!(2, actual.number_of_groupings());
+    }
+    #[test]
     fn generic_groupings_from_json_file_equals_organizations_from_json_file() {
         let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
         let groupings = Groupings::from_json_file(
@@ -0,0 +1,162 @@
+use super::*;
+
+/// Structural diagnostics for an FBAS in stellarbeat-style JSON, collected without running any
+/// quorum analyses. Works on the raw JSON, so that it can report problems that get silently
+/// patched when reading the JSON into an `Fbas` (like references to unknown validators).
+/// ```
+/// use fbas_analyzer::FbasLint;
+///
+/// let input = r#"[
+///     {
+///         "publicKey": "Alice",
+///         "quorumSet": { "threshold": 2, "validators": ["Alice", "Bob", "Mallory"] }
+///     },
+///     {
+///         "publicKey": "Bob",
+///         "quorumSet": { "threshold": 1, "validators": ["Alice"] }
+///     }
+/// ]"#;
+/// let lint = FbasLint::from_json_str(&input).unwrap();
+/// assert_eq!(vec![("Alice".to_string(), "Mallory".to_string())], lint.dangling_references);
+/// assert_eq!(vec!["Bob"], lint.nodes_not_in_own_quorum_set);
+/// assert!(!lint.is_clean());
+///
+/// // Unlike `Fbas::from_json_str`, input that doesn't parse yields an error instead of a panic.
+/// assert!(FbasLint::from_json_str(r#"[{ "publicKey": 1 }]"#).is_err());
+/// ```
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct FbasLint {
+    pub number_of_nodes: usize,
+    pub number_of_satisfiable_nodes: usize,
+    /// Pairs of (node, unknown validator referenced in the node's quorum set).
+    pub dangling_references: Vec<(PublicKey, PublicKey)>,
+    /// Nodes with a (possibly inner) quorum set whose threshold can never be reached, because it
+    /// is larger than the number of its validators and inner quorum sets, or is zero.
+    pub unsatisfiable_thresholds: Vec<PublicKey>,
+    /// Nodes with a quorum set that doesn't include the node itself.
+    pub nodes_not_in_own_quorum_set: Vec<PublicKey>,
+    /// Public keys that occur more than once, ignoring case and surrounding whitespace.
+    pub duplicate_public_keys: Vec<PublicKey>,
+}
+impl FbasLint {
+    pub fn from_json_str(json: &str) -> serde_json::Result<Self> {
+        let raw_nodes = serde_json::from_str::<RawFbasLayout>(json)?.into_raw_nodes();
+        let known_public_keys: HashSet<&PublicKey> =
+            raw_nodes.iter().map(|node| &node.public_key).collect();
+
+        let mut lint = FbasLint {
+            number_of_nodes: raw_nodes.len(),
+            ..Default::default()
+        };
+        let mut seen_normalized_public_keys = HashSet::new();
+        for raw_node in raw_nodes.iter() {
+            let public_key = &raw_node.public_key;
+            if !seen_normalized_public_keys.insert(public_key.trim().to_uppercase()) {
+                lint.duplicate_public_keys.push(public_key.clone());
+            }
+            if let Some(raw_quorum_set) = raw_node.quorum_set.as_ref() {
+                let validators = raw_quorum_set.contained_validators();
+                lint.dangling_references.extend(
+                    validators
+                        .iter()
+                        .filter(|&&validator| !known_public_keys.contains(validator))
+                        .map(|&validator| (public_key.clone(), validator.clone())),
+                );
+                if raw_quorum_set.has_unreachable_threshold() {
+                    lint.unsatisfiable_thresholds.push(public_key.clone());
+                }
+                if !validators.contains(&public_key) {
+                    lint.nodes_not_in_own_quorum_set.push(public_key.clone());
+                }
+            }
+        }
+        lint.number_of_satisfiable_nodes =
+            Fbas::from_raw(RawFbas(raw_nodes)).satisfiable_nodes().len();
+        Ok(lint)
+    }
+    /// Like [`FbasLint::from_json_str`]; read and parse errors are both returned as `io::Error`.
+    pub fn from_json_file(path: &Path) -> io::Result<Self> {
+        Ok(Self::from_json_str(&fs::read_to_string(path)?)?)
+    }
+    /// `true` if none of the checked problems were found.
+    pub fn is_clean(&self) -> bool {
+        self.dangling_references.is_empty()
+            && self.unsatisfiable_thresholds.is_empty()
+            && self.nodes_not_in_own_quorum_set.is_empty()
+            && self.duplicate_public_keys.is_empty()
+    }
+}
+
+impl RawQuorumSet {
+    fn contained_validators(&self) -> Vec<&PublicKey> {
+        let mut validators: Vec<&PublicKey> = self.validators.iter().collect();
+        for inner_quorum_set in self.inner_quorum_sets.iter() {
+            validators.extend(inner_quorum_set.contained_validators());
+        }
+        validators
+    }
+    fn has_unreachable_threshold(&self) -> bool {
+        let n_members = self.validators.len() + self.inner_quorum_sets.len();
+        self.threshold == 0
+            || self.threshold > n_members as u64
+            || self
+                .inner_quorum_sets
+                .iter()
+                .any(|inner_quorum_set| inner_quorum_set.has_unreachable_threshold())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lint_finds_structural_problems() {
+        let lint = FbasLint::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 2, "validators": ["n0", "n1", "n9"] }
+            },
+            {
+                "publicKey": "n1",
+                "quorumSet": { "threshold": 2, "validators": ["n0"], "innerQuorumSets": [
+                    { "threshold": 3, "validators": ["n1", "n2"] }
+                ]}
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 1, "validators": ["n0"] }
+            },
+            {
+                "publicKey": " N2"
+            }
+        ]"#,
+        )
+        .unwrap();
+        let expected = FbasLint {
+            number_of_nodes: 4,
+            number_of_satisfiable_nodes: 0,
+            dangling_references: vec![("n0".into(), "n9".into())],
+            unsatisfiable_thresholds: vec!["n1".into()],
+            nodes_not_in_own_quorum_set: vec!["n2".into()],
+            duplicate_public_keys: vec![" N2".into()],
+        };
+        assert_eq!(expected, lint);
+        assert!(!lint.is_clean());
+    }
+
+    #[test]
+    fn lint_of_correct_fbas_is_clean() {
+        let lint = FbasLint::from_json_file(Path::new("test_data/correct_trivial.json")).unwrap();
+        assert!(lint.is_clean());
+        assert_eq!(lint.number_of_nodes, lint.number_of_satisfiable_nodes);
+    }
+
+    #[test]
+    fn lint_of_unparsable_json_is_an_error() {
+        assert!(FbasLint::from_json_str(r#"[{ "publicKey": 1 }]"#).is_err());
+        assert!(FbasLint::from_json_str("not json").is_err());
+        assert!(FbasLint::from_json_file(Path::new("test_data/does_not_exist.json")).is_err());
+    }
+}
@@ -12,6 +12,8 @@ macro_rules! read_or_panic {
 }
 
 mod core_types;
+#[cfg(feature = "url-input")]
+pub use core_types::FetchFbasError;
 use core_types::*;
 
 mod groupings;
@@ -19,6 +21,9 @@ mod groupings;
 mod filtered_nodes;
 pub use filtered_nodes::FilteredNodes;
 
+mod lint;
+pub use lint::FbasLint;
+
 mod results;
 pub use results::*;
 
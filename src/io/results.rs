@@ -10,6 +10,24 @@ pub struct PrettyQuorumSet {
     pub inner_quorum_sets: Vec<PrettyQuorumSet>,
 }
 
+/// A node set as public keys, grouped by the names of the groupings the nodes belong to. Nodes
+/// that belong to no grouping are listed under `"ungrouped"`. Serializes into a JSON object like
+/// `{"org name": ["pk1", "pk2"], "ungrouped": ["pk3"]}`.
+#[derive(Debug, Serialize, Default, PartialEq)]
+pub struct PrettyGroupedNodeSet(pub BTreeMap<String, Vec<PublicKey>>);
+
+/// A `NodeIdSetVecResult` in the compact, shrunken ID space that it is stored in, together with
+/// the map for translating shrunken IDs back: shrunken ID `i` corresponds to original node ID
+/// `id_map[i]` and, if included, public key `public_keys[i]`. Serializes into a JSON object like
+/// `{"node_sets": [[0, 1], [1, 2]], "id_map": [3, 7, 9]}`.
+#[derive(Debug, Serialize, Default, PartialEq)]
+pub struct ShrunkenNodeSets {
+    pub node_sets: Vec<Vec<NodeId>>,
+    pub id_map: Vec<NodeId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_keys: Option<Vec<PublicKey>>,
+}
+
 macro_rules! json_format_single_line {
     ($x:expr) => {
         serde_json::to_string(&$x).expect("Error formatting as JSON")
@@ -27,6 +45,23 @@ pub trait AnalysisResult: Sized + Serialize {
         self.into_id_string()
     }
     fn into_describe_string(self) -> String;
+    /// Like `into_describe_string`, but using JSON objects with named fields where the terse
+    /// description would be positional.
+    fn into_describe_json_string(self) -> String {
+        self.into_describe_string()
+    }
+    /// `(key, value)` pairs with one metric each, for diff-friendly flat output. Keys start with
+    /// `result_name`; results that are described by several metrics use dotted sub-keys.
+    fn into_flat_entries(self, result_name: &str) -> Vec<(String, String)> {
+        vec![(result_name.to_string(), self.into_describe_string())]
+    }
+    /// Serializes the result as MessagePack, a compact binary alternative to `into_id_string`
+    /// (same structure, struct fields as named map entries). Requires the `msgpack-output`
+    /// feature.
+    #[cfg(feature = "msgpack-output")]
+    fn into_msgpack(self) -> Vec<u8> {
+        rmp_serde::to_vec_named(&self).expect("Error formatting as MessagePack")
+    }
 }
 
 // semantically strange, but for convenience
@@ -75,6 +110,9 @@ impl AnalysisResult for Vec<QuorumSet> {
     fn into_describe_string(self) -> String {
         self.into_id_string()
     }
+    fn into_flat_entries(self, result_name: &str) -> Vec<(String, String)> {
+        vec![(format!("{}.count", result_name), self.len().to_string())]
+    }
 }
 
 impl AnalysisResult for NodeIdSetResult {
@@ -87,6 +125,9 @@ impl AnalysisResult for NodeIdSetResult {
     fn into_describe_string(self) -> String {
         self.len().to_string()
     }
+    fn into_flat_entries(self, result_name: &str) -> Vec<(String, String)> {
+        vec![(format!("{}.size", result_name), self.len().to_string())]
+    }
 }
 impl Serialize for NodeIdSetResult {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -97,6 +138,18 @@ impl Serialize for NodeIdSetResult {
     }
 }
 
+impl<'de> Deserialize<'de> for NodeIdSetResult {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let node_ids = Vec::<NodeId>::deserialize(deserializer)?;
+        Ok(NodeIdSetResult::from(
+            node_ids.into_iter().collect::<NodeIdSet>(),
+        ))
+    }
+}
+
 impl AnalysisResult for NodeIdSetVecResult {
     fn into_id_string(self) -> String {
         json_format_single_line!(self.into_vec_vec())
@@ -107,6 +160,34 @@ impl AnalysisResult for NodeIdSetVecResult {
     fn into_describe_string(self) -> String {
         json_format_single_line!(self.describe())
     }
+    fn into_describe_json_string(self) -> String {
+        json_format_single_line!(self.description())
+    }
+    fn into_flat_entries(self, result_name: &str) -> Vec<(String, String)> {
+        let description = self.description();
+        vec![
+            (
+                format!("{}.count", result_name),
+                description.count.to_string(),
+            ),
+            (
+                format!("{}.distinct_nodes", result_name),
+                description.distinct_nodes.to_string(),
+            ),
+            (
+                format!("{}.min", result_name),
+                description.size.min.to_string(),
+            ),
+            (
+                format!("{}.max", result_name),
+                description.size.max.to_string(),
+            ),
+            (
+                format!("{}.mean", result_name),
+                json_format_single_line!(description.size.mean),
+            ),
+        ]
+    }
 }
 impl Serialize for NodeIdSetVecResult {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -117,6 +198,18 @@ impl Serialize for NodeIdSetVecResult {
     }
 }
 
+#[cfg(feature = "msgpack-output")]
+impl AnalysisReport {
+    /// Serializes the report as MessagePack. Requires the `msgpack-output` feature.
+    pub fn into_msgpack(self) -> Vec<u8> {
+        rmp_serde::to_vec_named(&self).expect("Error formatting as MessagePack")
+    }
+    /// Reads a report serialized via `into_msgpack`. Requires the `msgpack-output` feature.
+    pub fn from_msgpack(bytes: &[u8]) -> Self {
+        rmp_serde::from_slice(bytes).expect("Error parsing MessagePack")
+    }
+}
+
 impl QuorumSet {
     pub fn into_pretty_quorum_set(
         self,
@@ -150,6 +243,11 @@ impl QuorumSet {
             inner_quorum_sets,
         }
     }
+    /// Formats the quorum set as pretty-printed JSON, with validators identified by their public
+    /// keys (or grouping names, if `groupings` is passed) instead of node IDs.
+    pub fn to_pretty_json(&self, fbas: &Fbas, groupings: Option<&Groupings>) -> String {
+        self.clone().into_pretty_string(fbas, groupings)
+    }
 }
 
 impl NodeIdSetResult {
@@ -163,6 +261,27 @@ impl NodeIdSetResult {
             to_public_keys(&self.unwrap(), fbas)
         }
     }
+    /// Transforms result into public keys grouped by grouping name (see `PrettyGroupedNodeSet`).
+    /// Unlike `into_pretty_vec`, this keeps track of which nodes back each grouping, so it is
+    /// meant for results that haven't been merged by `groupings`.
+    pub fn into_pretty_grouped_node_set(
+        self,
+        fbas: &Fbas,
+        groupings: &Groupings,
+    ) -> PrettyGroupedNodeSet {
+        let mut grouped = BTreeMap::new();
+        for node_id in self.unwrap().iter() {
+            let group_name = match groupings.get_by_member(node_id) {
+                Some(grouping) => grouping.name.clone(),
+                None => String::from("ungrouped"),
+            };
+            grouped
+                .entry(group_name)
+                .or_insert_with(Vec::new)
+                .push(fbas.nodes[node_id].public_key.clone());
+        }
+        PrettyGroupedNodeSet(grouped)
+    }
 }
 
 impl NodeIdSetVecResult {
@@ -190,6 +309,59 @@ impl NodeIdSetVecResult {
             })
             .collect()
     }
+    /// Transforms result into its shrunken form plus ID map (see `ShrunkenNodeSets`), without
+    /// unshrinking. If the result isn't shrunken, the ID map is the identity. If `fbas` is passed
+    /// (it should be the one used for analysis), the map additionally lists public keys.
+    pub fn to_shrunken_node_sets(&self, fbas: Option<&Fbas>) -> ShrunkenNodeSets {
+        let id_map: Vec<NodeId> = if let Some(unshrink_table) = self.unshrink_table.as_ref() {
+            unshrink_table.clone()
+        } else {
+            let max_id = involved_nodes(&self.shrunken_node_sets).iter().max();
+            (0..max_id.map_or(0, |id| id + 1)).collect()
+        };
+        ShrunkenNodeSets {
+            node_sets: self
+                .shrunken_node_sets
+                .iter()
+                .map(|node_set| node_set.iter().collect())
+                .collect(),
+            public_keys: fbas.map(|fbas| to_public_keys(id_map.iter().copied(), fbas)),
+            id_map,
+        }
+    }
+    /// Formats result as a CSV membership matrix, with one row per node of `fbas` (labelled by
+    /// public key, in node ID order), one column per node set (labelled by its index) and
+    /// cells that are `1` if the node is contained in the node set and `0` otherwise. Public keys
+    /// are quoted as per RFC 4180 where necessary.
+    pub fn to_membership_csv(&self, fbas: &Fbas) -> String {
+        let node_sets = self.clone().unwrap();
+        let mut csv = String::from("public_key");
+        for index in 0..node_sets.len() {
+            csv.push_str(&format!(",{}", index));
+        }
+        csv.push('\n');
+        for (node_id, node) in fbas.nodes.iter().enumerate() {
+            csv.push_str(&to_csv_field(&node.public_key));
+            for node_set in node_sets.iter() {
+                csv.push_str(if node_set.contains(node_id) {
+                    ",1"
+                } else {
+                    ",0"
+                });
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Quotes `field` as per RFC 4180 if it contains commas, double quotes or line breaks.
+fn to_csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
 }
 
 /// Resolve the pretty names for a collection of node IDs.
@@ -327,7 +499,24 @@ mod tests {
             // [1,23,29,36]
             r#"["LOBSTR","SatoshiPay","COINQVEST Limited","Keybase"]"#
         );
-        assert_eq!(mq.into_describe_string(), "[5,5,[4,4,4.0],[0,0,0,0,5]]");
+        assert_eq!(
+            mq.clone().into_describe_string(),
+            "[5,5,[4,4,4.0],[0,0,0,0,5]]"
+        );
+        assert_eq!(
+            mq.clone().into_flat_entries("mq"),
+            vec![
+                ("mq.count".to_string(), "5".to_string()),
+                ("mq.distinct_nodes".to_string(), "5".to_string()),
+                ("mq.min".to_string(), "4".to_string()),
+                ("mq.max".to_string(), "4".to_string()),
+                ("mq.mean".to_string(), "4.0".to_string()),
+            ]
+        );
+        assert_eq!(
+            mq.into_describe_json_string(),
+            r#"{"count":5,"distinct_nodes":5,"size":{"min":4,"max":4,"mean":4.0},"histogram":[0,0,0,0,5]}"#
+        );
     }
 
     #[test]
@@ -442,6 +631,40 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn into_pretty_grouped_node_set_nests_public_keys_by_org() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "Jim"
+            },
+            {
+                "publicKey": "Jon"
+            },
+            {
+                "publicKey": "Alex"
+            },
+            {
+                "publicKey": "Bob"
+            }
+            ]"#,
+        );
+        let organizations = Groupings::organizations_from_json_str(
+            r#"[
+            {
+                "name": "J Mafia",
+                "validators": [ "Jim", "Jon" ]
+            }
+            ]"#,
+            &fbas,
+        );
+        let result = NodeIdSetResult::new(bitset![0, 1, 3], None);
+        let expected = r#"{"J Mafia":["Jim","Jon"],"ungrouped":["Bob"]}"#;
+        let actual =
+            json_format_single_line!(result.into_pretty_grouped_node_set(&fbas, &organizations));
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn into_pretty_quorum_set() {
         let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
@@ -468,4 +691,130 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn quorum_set_to_pretty_json_uses_public_keys() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct_trivial.json"));
+        let quorum_set = fbas.get_quorum_set(0).unwrap();
+
+        let actual: PrettyQuorumSet =
+            serde_json::from_str(&quorum_set.to_pretty_json(&fbas, None)).unwrap();
+        let expected = quorum_set.into_pretty_quorum_set(&fbas, None);
+
+        assert_eq!(expected, actual);
+        assert!(actual
+            .validators
+            .iter()
+            .all(|validator| fbas.get_node_id(validator).is_some()));
+    }
+
+    #[test]
+    fn to_membership_csv_has_one_row_per_node_and_one_column_per_set() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+        let minimal_quorums = analysis.minimal_quorums();
+        let first_quorum = minimal_quorums.clone().unwrap()[0].clone();
+
+        let csv = minimal_quorums.to_membership_csv(&fbas);
+        let rows: Vec<Vec<&str>> = csv.lines().map(|line| line.split(',').collect()).collect();
+
+        assert_eq!(fbas.number_of_nodes() + 1, rows.len());
+        assert!(rows
+            .iter()
+            .all(|row| row.len() == minimal_quorums.len() + 1));
+        assert_eq!(vec!["public_key", "0"], rows[0][..2]);
+        for node_id in 0..fbas.number_of_nodes() {
+            let expected = if first_quorum.contains(node_id) {
+                "1"
+            } else {
+                "0"
+            };
+            assert_eq!(fbas.nodes[node_id].public_key, rows[node_id + 1][0]);
+            assert_eq!(expected, rows[node_id + 1][1]);
+        }
+    }
+
+    #[test]
+    fn to_membership_csv_quotes_public_keys() {
+        let fbas = Fbas::from_json_str(
+            r#"[
+            {
+                "publicKey": "n0,a",
+                "quorumSet": { "threshold": 2, "validators": ["n0,a", "n1 \"b\""] }
+            },
+            {
+                "publicKey": "n1 \"b\"",
+                "quorumSet": { "threshold": 2, "validators": ["n0,a", "n1 \"b\""] }
+            },
+            {
+                "publicKey": "n2",
+                "quorumSet": { "threshold": 1, "validators": ["n0,a"] }
+            }
+        ]"#,
+        );
+        let minimal_quorums = Analysis::new(&fbas).minimal_quorums();
+
+        let expected = "public_key,0\n\"n0,a\",1\n\"n1 \"\"b\"\"\",1\nn2,0\n";
+        let actual = minimal_quorums.to_membership_csv(&fbas);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn shrunken_node_sets_reconstruct_original_ids() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json"));
+        let analysis = Analysis::new(&fbas);
+        let mq = analysis.minimal_quorums();
+
+        let shrunken = mq.to_shrunken_node_sets(Some(&fbas));
+        assert!(shrunken.id_map.len() < fbas.number_of_nodes());
+        let reconstructed: Vec<NodeIdSet> = shrunken
+            .node_sets
+            .iter()
+            .map(|node_set| node_set.iter().map(|&id| shrunken.id_map[id]).collect())
+            .collect();
+        assert_eq!(mq.clone().unwrap(), reconstructed);
+
+        let public_keys = shrunken.public_keys.clone().unwrap();
+        for (shrunken_id, &original_id) in shrunken.id_map.iter().enumerate() {
+            assert_eq!(fbas.nodes[original_id].public_key, public_keys[shrunken_id]);
+        }
+        let json = serde_json::to_value(&shrunken).unwrap();
+        assert_eq!(json["id_map"], serde_json::json!(shrunken.id_map));
+    }
+
+    #[test]
+    fn unshrunken_node_sets_have_identity_id_map() {
+        let result = NodeIdSetVecResult::from(bitsetvec![{0, 2}, {1, 2}]);
+        let expected = ShrunkenNodeSets {
+            node_sets: vec![vec![0, 2], vec![1, 2]],
+            id_map: vec![0, 1, 2],
+            public_keys: None,
+        };
+        assert_eq!(expected, result.to_shrunken_node_sets(None));
+    }
+
+    #[test]
+    #[cfg(feature = "msgpack-output")]
+    fn full_report_msgpack_round_trip() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/correct.json")).to_core();
+        let report = Analysis::new(&fbas).full_report(None);
+
+        let bytes = report.clone().into_msgpack();
+        assert_eq!(report, AnalysisReport::from_msgpack(&bytes));
+    }
+
+    #[test]
+    #[cfg(feature = "msgpack-output")]
+    fn msgpack_is_more_compact_than_json() {
+        let fbas = Fbas::from_json_file(Path::new("test_data/stellarbeat_nodes_2019-09-17.json"));
+        let minimal_quorums = Analysis::new(&fbas).minimal_quorums();
+
+        let msgpack = minimal_quorums.clone().into_msgpack();
+        let json = minimal_quorums.clone().into_id_string();
+        assert!(msgpack.len() < json.len());
+        assert_eq!(
+            minimal_quorums.into_vec_vec(),
+            rmp_serde::from_slice::<Vec<Vec<NodeId>>>(&msgpack).unwrap()
+        );
+    }
 }
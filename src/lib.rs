@@ -40,8 +40,15 @@ mod core_types;
 mod io;
 
 pub use analysis::*;
-pub use core_types::{Fbas, Groupings, NodeId, NodeIdSet, QuorumSet};
-pub use io::{to_grouping_names, to_public_keys, AnalysisResult, FilteredNodes, PrettyQuorumSet};
+pub use core_types::{
+    Fbas, Groupings, NestingDepthPolicy, NodeId, NodeIdSet, QuorumSet, QuorumSetsTooDeep,
+};
+#[cfg(feature = "url-input")]
+pub use io::FetchFbasError;
+pub use io::{
+    to_grouping_names, to_public_keys, AnalysisResult, FbasLint, FilteredNodes,
+    PrettyGroupedNodeSet, PrettyQuorumSet, ShrunkenNodeSets,
+};
 
 use core_types::*;
 
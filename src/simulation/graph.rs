@@ -2,8 +2,9 @@ use super::*;
 
 use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Graph {
     // outgoing edges per node
     pub(crate) outlinks: Vec<Vec<NodeId>>,
@@ -13,6 +14,14 @@ impl Graph {
         info!("New graph with {} nodes.", outlinks.len());
         Graph { outlinks }
     }
+    /// Reads a graph as written by [`Graph::to_json_string`]. Useful for reusing an (expensive)
+    /// generated graph across several simulations.
+    pub fn from_json_str(json: &str) -> Self {
+        serde_json::from_str(json).expect("Error parsing graph JSON")
+    }
+    pub fn to_json_string(&self) -> String {
+        serde_json::to_string(&self).expect("Error converting graph to JSON!")
+    }
     /// Build a graph where every node is connected to every other node (i.e., a complete graph).
     pub fn new_full_mesh(n: usize) -> Self {
         Self::new((0..n).map(|i| (0..i).chain(i + 1..n).collect()).collect())
@@ -37,13 +46,21 @@ impl Graph {
     }
     /// Build a scale-free graph using the Barabási–Albert (BA) model
     pub fn new_random_scale_free(n: usize, m0: usize, m: usize) -> Self {
+        Self::new_random_scale_free_with_rng(n, m0, m, &mut thread_rng())
+    }
+    /// Like `new_random_scale_free`, but using the passed RNG (e.g., a seeded one).
+    pub fn new_random_scale_free_with_rng(
+        n: usize,
+        m0: usize,
+        m: usize,
+        rng: &mut impl Rng,
+    ) -> Self {
         assert!(
             0 < m && m <= m0 && m <= n,
             "Parameters for Barabási–Albert don't make sense."
         );
 
         let mut outlinks: Vec<Vec<NodeId>> = vec![vec![]; n];
-        let mut rng = thread_rng();
 
         macro_rules! connect {
             ($a:expr, $b:expr) => {
@@ -66,7 +83,7 @@ impl Graph {
             let mut possible_targets: Vec<NodeId> = (0..i).collect();
             for _ in 0..m {
                 let j = possible_targets
-                    .choose_weighted(&mut rng, |&x| outlinks[x].len())
+                    .choose_weighted(rng, |&x| outlinks[x].len())
                     .unwrap()
                     .to_owned();
                 connect!(i, j);
@@ -81,13 +98,21 @@ impl Graph {
     /// Build a small world graph using the Watts-Strogatz model
     /// Not super optimized but OK for networks below 10^5 nodes.
     pub fn new_random_small_world(n: usize, k: usize, beta: f64) -> Self {
+        Self::new_random_small_world_with_rng(n, k, beta, &mut thread_rng())
+    }
+    /// Like `new_random_small_world`, but using the passed RNG (e.g., a seeded one).
+    pub fn new_random_small_world_with_rng(
+        n: usize,
+        k: usize,
+        beta: f64,
+        rng: &mut impl Rng,
+    ) -> Self {
         assert!(
             k % 2 == 0,
             "For the Watts-Strogatz model, `k` must be an even number!"
         );
 
         let mut matrix = vec![vec![false; n]; n];
-        let mut rng = thread_rng();
 
         // step 1: construct a ring lattice
         for i in 0..n {
@@ -118,7 +143,7 @@ impl Graph {
                 }
             }
             for j in to_be_rewired.drain(..) {
-                let chosen_node = possible_targets[i].choose(&mut rng);
+                let chosen_node = possible_targets[i].choose(rng);
                 if let Some(&newj) = chosen_node {
                     //rewire
                     matrix[i][j] = false;
@@ -148,12 +173,15 @@ impl Graph {
     }
     /// Shuffle the node IDs
     pub fn shuffled(self) -> Self {
+        self.shuffled_with_rng(&mut thread_rng())
+    }
+    /// Like `shuffled`, but using the passed RNG (e.g., a seeded one).
+    pub fn shuffled_with_rng(self, rng: &mut impl Rng) -> Self {
         let n = self.outlinks.len();
-        let mut rng = thread_rng();
 
         // mappings
         let mut old_to_new: Vec<NodeId> = (0..n).collect();
-        old_to_new.shuffle(&mut rng);
+        old_to_new.shuffle(rng);
         let mut new_to_old = vec![0; n];
         for (old, &new) in old_to_new.iter().enumerate() {
             new_to_old[new] = old;
@@ -247,6 +275,13 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn json_round_trip_preserves_graph() {
+        let graph = Graph::new_random_scale_free(50, 4, 2);
+        let json = graph.to_json_string();
+        assert_eq!(graph, Graph::from_json_str(&json));
+    }
+
     #[test]
     fn tiered_full_mesh() {
         let expected = Graph {
@@ -317,6 +352,23 @@ mod tests {
         assert_ne!(graph1, graph2);
     }
 
+    #[test]
+    fn seeded_random_graphs_are_reproducible() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        let (n, k, beta) = (100, 10, 0.05);
+        let graph1 =
+            Graph::new_random_small_world_with_rng(n, k, beta, &mut StdRng::seed_from_u64(7));
+        let graph2 =
+            Graph::new_random_small_world_with_rng(n, k, beta, &mut StdRng::seed_from_u64(7));
+        assert_eq!(graph1, graph2);
+        let graph1 =
+            Graph::new_random_scale_free_with_rng(100, 3, 2, &mut StdRng::seed_from_u64(7));
+        let graph2 =
+            Graph::new_random_scale_free_with_rng(100, 3, 2, &mut StdRng::seed_from_u64(7));
+        assert_eq!(graph1, graph2);
+    }
+
     #[test]
     fn small_world_graph_with_big_k_is_random() {
         let (n, k, beta) = (120, 110, 0.05);
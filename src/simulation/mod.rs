@@ -2,8 +2,9 @@ use super::*;
 
 use std::rc::Rc;
 
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::SeedableRng;
 
 pub mod monitors;
 pub mod qsc;
@@ -11,11 +12,19 @@ pub mod qsc;
 mod graph;
 pub use graph::Graph;
 
+/// Simulates the evolution of an FBAS in which nodes configure their quorum sets using a
+/// `QuorumSetConfigurator`.
+///
+/// The order in which nodes reevaluate their quorum sets is random. For reproducible simulations,
+/// use `new_seeded`, together with deterministic QSCs (e.g., a seeded `RandomQsc`) and seeded graph
+/// constructors (e.g., `Graph::new_random_scale_free_with_rng`).
 #[derive(Clone)]
 pub struct Simulator {
     fbas: Fbas,
     qsc: Rc<dyn QuorumSetConfigurator>,
     monitor: Rc<dyn SimulationMonitor>,
+    frozen_nodes: NodeIdSet,
+    rng: StdRng,
 }
 impl Simulator {
     pub fn new(
@@ -23,7 +32,31 @@ impl Simulator {
         qsc: Rc<dyn QuorumSetConfigurator>,
         monitor: Rc<dyn SimulationMonitor>,
     ) -> Self {
-        Simulator { fbas, qsc, monitor }
+        Self::new_with_rng(fbas, qsc, monitor, StdRng::from_entropy())
+    }
+    /// Like `new`, but the order in which nodes reevaluate their quorum sets is derived
+    /// deterministically from `seed`.
+    pub fn new_seeded(
+        fbas: Fbas,
+        qsc: Rc<dyn QuorumSetConfigurator>,
+        monitor: Rc<dyn SimulationMonitor>,
+        seed: u64,
+    ) -> Self {
+        Self::new_with_rng(fbas, qsc, monitor, StdRng::seed_from_u64(seed))
+    }
+    fn new_with_rng(
+        fbas: Fbas,
+        qsc: Rc<dyn QuorumSetConfigurator>,
+        monitor: Rc<dyn SimulationMonitor>,
+        rng: StdRng,
+    ) -> Self {
+        Simulator {
+            fbas,
+            qsc,
+            monitor,
+            frozen_nodes: bitset![],
+            rng,
+        }
     }
     /// Get the contained FBAS, effectively ending the simulation
     pub fn finalize(self) -> Fbas {
@@ -39,23 +72,30 @@ impl Simulator {
             self.simulate_global_reevaluation(self.fbas.number_of_nodes());
         }
     }
-    /// Make all nodes reevaluate and update their quorum sets using `qsc`, up to
+    /// Like `simulate_growth`, but nodes in `frozen` keep their current quorum sets, i.e., they
+    /// are skipped during all reevaluations. New nodes are not frozen.
+    pub fn simulate_growth_freezing(&mut self, nodes_to_spawn: usize, frozen: NodeIdSet) {
+        self.frozen_nodes = frozen;
+        self.simulate_growth(nodes_to_spawn);
+        self.frozen_nodes.clear();
+    }
+    /// Make all (non-frozen) nodes reevaluate and update their quorum sets using `qsc`, up to
     /// `maximum_number_of_rounds` or until the global configuration has stabilizied (no more
     /// changes happen).
     ///
-    /// Returns the number of reevaluation rounds made.
+    /// Returns the number of reevaluation rounds made. For the number of changes per round, use a
+    /// `monitors::ConvergenceMonitor`.
     pub fn simulate_global_reevaluation(&mut self, maximum_number_of_rounds: usize) -> usize {
         let mut stable = false;
         let mut next_round_number = 0;
 
         // Visit nodes in random order each time
         let mut order: Vec<NodeId> = (0..self.fbas.nodes.len()).collect();
-        let mut rng = thread_rng();
 
         self.monitor.register_event(StartGlobalReevaluation);
 
         while !stable && next_round_number < maximum_number_of_rounds {
-            order.shuffle(&mut rng);
+            order.shuffle(&mut self.rng);
             stable = !self.simulate_global_reevaluation_round(&order).had_change();
             next_round_number += 1;
         }
@@ -69,6 +109,9 @@ impl Simulator {
         self.monitor.register_event(StartGlobalReevaluationRound);
         let mut any_change = NoChange;
         for &node_id in order {
+            if self.frozen_nodes.contains(node_id) {
+                continue;
+            }
             let change = self.qsc.configure(node_id, &mut self.fbas);
             any_change.update(change);
             self.monitor
@@ -179,6 +222,45 @@ mod tests {
         assert_eq!(number_of_rounds, 2);
     }
 
+    #[test]
+    fn convergence_monitor_records_changes_per_round() {
+        let monitor = Rc::new(ConvergenceMonitor::new());
+        let mut simulator = Simulator::new(
+            Fbas::new_generic_unconfigured(8),
+            Rc::new(SuperSafeQsc),
+            Rc::clone(&monitor) as Rc<dyn SimulationMonitor>,
+        );
+        assert!(monitor.changes_per_round().is_empty());
+
+        simulator.simulate_global_reevaluation(1000000);
+        assert_eq!(vec![8, 0], monitor.changes_per_round());
+
+        simulator.simulate_growth(1);
+        assert_eq!(Some(&0), monitor.changes_per_round().last());
+        assert_eq!(2, monitor.changes_per_round_per_reevaluation().len());
+    }
+
+    #[test]
+    fn growth_freezing_leaves_frozen_quorum_sets_unchanged() {
+        let fbas = Fbas::from_json_file(std::path::Path::new("test_data/correct_trivial.json"));
+        let mut simulator =
+            Simulator::new(fbas.clone(), Rc::new(SuperSafeQsc), Rc::new(DummyMonitor));
+        simulator.simulate_growth_freezing(2, fbas.all_nodes());
+        let fbas_after = simulator.finalize();
+
+        assert_eq!(5, fbas_after.number_of_nodes());
+        for node_id in fbas.all_nodes().iter() {
+            assert_eq!(fbas.nodes[node_id], fbas_after.nodes[node_id]);
+        }
+        let expected_quorum_set = QuorumSet {
+            threshold: 5,
+            validators: vec![0, 1, 2, 3, 4],
+            inner_quorum_sets: vec![],
+        };
+        assert_eq!(expected_quorum_set, fbas_after.nodes[3].quorum_set);
+        assert_eq!(expected_quorum_set, fbas_after.nodes[4].quorum_set);
+    }
+
     #[test]
     fn global_reevaluation_visits_in_random_order() {
         let monitor = Rc::new(DebugMonitor::new());
@@ -208,4 +290,21 @@ mod tests {
         assert_eq!(orderings.len(), 2);
         assert_ne!(orderings[0], orderings[1]);
     }
+
+    #[test]
+    fn seeded_simulations_are_reproducible() {
+        let simulate = |seed| {
+            let monitor = Rc::new(DebugMonitor::new());
+            let mut simulator = Simulator::new_seeded(
+                Fbas::new_generic_unconfigured(32),
+                Rc::new(RandomQsc::new_simple(8).with_seed(seed)),
+                Rc::clone(&monitor) as Rc<dyn SimulationMonitor>,
+                seed,
+            );
+            simulator.simulate_global_reevaluation(2);
+            (simulator.finalize(), monitor.events_clone())
+        };
+        assert_eq!(simulate(42), simulate(42));
+        assert_ne!(simulate(42), simulate(43));
+    }
 }
@@ -32,3 +32,50 @@ impl SimulationMonitor for DebugMonitor {
         self.recorded_events.borrow_mut().push(event);
     }
 }
+
+/// Records how many nodes changed their quorum sets in each global reevaluation round, i.e., how
+/// each global reevaluation converged.
+#[derive(Default)]
+pub struct ConvergenceMonitor {
+    changes_per_round_per_reevaluation: RefCell<Vec<Vec<usize>>>,
+}
+impl ConvergenceMonitor {
+    pub fn new() -> Self {
+        ConvergenceMonitor {
+            changes_per_round_per_reevaluation: RefCell::new(vec![]),
+        }
+    }
+    /// Number of quorum set changes per round of the most recent global reevaluation. If the
+    /// reevaluation converged, the last entry is `0`.
+    pub fn changes_per_round(&self) -> Vec<usize> {
+        self.changes_per_round_per_reevaluation
+            .borrow()
+            .last()
+            .cloned()
+            .unwrap_or_default()
+    }
+    /// Like `changes_per_round`, but for each global reevaluation so far (e.g., one per added
+    /// node when simulating growth).
+    pub fn changes_per_round_per_reevaluation(&self) -> Vec<Vec<usize>> {
+        self.changes_per_round_per_reevaluation.borrow().clone()
+    }
+}
+impl SimulationMonitor for ConvergenceMonitor {
+    fn register_event(&self, event: Event) {
+        let mut traces = self.changes_per_round_per_reevaluation.borrow_mut();
+        match event {
+            StartGlobalReevaluation => traces.push(vec![]),
+            StartGlobalReevaluationRound => {
+                if let Some(trace) = traces.last_mut() {
+                    trace.push(0);
+                }
+            }
+            QuorumSetChange(_, Change) => {
+                if let Some(changes) = traces.last_mut().and_then(|trace| trace.last_mut()) {
+                    *changes += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -4,7 +4,8 @@ use super::*;
 /// (if everybody plays along and there are no sybils attackers).
 ///
 /// Builds quorum sets containing all n nodes in the FBAS, with thresholds chosen such that
-/// a maximum of f nodes can fail, where (n-1) < (3f+1) <= n.
+/// a maximum of f nodes can fail, where (n-1) < (3f+1) <= n. Use [`RelativeIdealQsc`] for
+/// other thresholds.
 ///
 /// ```
 /// use fbas_analyzer::{Fbas, Analysis, bitset};
@@ -27,30 +28,53 @@ use super::*;
 pub struct IdealQsc;
 impl QuorumSetConfigurator for IdealQsc {
     fn configure(&self, node_id: NodeId, fbas: &mut Fbas) -> ChangeEffect {
-        let candidate = Self::build_new_configuration(fbas);
-        let existing = &mut fbas.nodes[node_id].quorum_set;
-        if candidate == *existing {
-            NoChange
-        } else {
-            *existing = candidate;
-            Change
-        }
+        let candidate = build_global_configuration(fbas, None);
+        update_quorum_set(node_id, fbas, candidate)
     }
 }
 impl IdealQsc {
     pub fn new() -> Self {
         IdealQsc {}
     }
-    fn build_new_configuration(fbas: &Fbas) -> QuorumSet {
-        let n = fbas.nodes.len();
-        let threshold: usize = calculate_67p_threshold(n);
-        let validators = (0..n).collect();
-        let inner_quorum_sets = vec![];
-        QuorumSet {
-            threshold,
-            validators,
-            inner_quorum_sets,
-        }
+}
+
+/// Like [`IdealQsc`], but with thresholds calculated relative to n via
+/// [`calculate_x_threshold`] instead of 67%. A `relative_threshold` of `1.` results in the same
+/// quorum sets as [`SuperSafeQsc`].
+pub struct RelativeIdealQsc {
+    relative_threshold: f64,
+}
+impl RelativeIdealQsc {
+    pub fn new(relative_threshold: f64) -> Self {
+        RelativeIdealQsc { relative_threshold }
+    }
+}
+impl QuorumSetConfigurator for RelativeIdealQsc {
+    fn configure(&self, node_id: NodeId, fbas: &mut Fbas) -> ChangeEffect {
+        let candidate = build_global_configuration(fbas, Some(self.relative_threshold));
+        update_quorum_set(node_id, fbas, candidate)
+    }
+}
+
+fn build_global_configuration(fbas: &Fbas, relative_threshold: Option<f64>) -> QuorumSet {
+    let n = fbas.nodes.len();
+    let threshold: usize = calculate_threshold(n, relative_threshold);
+    let validators = (0..n).collect();
+    let inner_quorum_sets = vec![];
+    QuorumSet {
+        threshold,
+        validators,
+        inner_quorum_sets,
+    }
+}
+
+fn update_quorum_set(node_id: NodeId, fbas: &mut Fbas, candidate: QuorumSet) -> ChangeEffect {
+    let existing = &mut fbas.nodes[node_id].quorum_set;
+    if candidate == *existing {
+        NoChange
+    } else {
+        *existing = candidate;
+        Change
     }
 }
 
@@ -69,4 +93,14 @@ mod tests {
         let expected = bitsetvec![{0, 1, 2}, {0, 1, 3}, {0, 2, 3}, {1, 2, 3}];
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn relative_ideal_qsc_makes_fbas_with_relative_threshold() {
+        let fbas = simulate!(RelativeIdealQsc::new(0.5), 4);
+        assert!(fbas.is_quorum(&bitset![0, 1]));
+        assert!(!Analysis::new(&fbas).has_quorum_intersection());
+
+        let fbas = simulate!(RelativeIdealQsc::new(1.), 4);
+        assert_eq!(fbas, simulate!(SuperSafeQsc::new(), 4));
+    }
 }
@@ -1,9 +1,14 @@
 use super::*;
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cell::RefCell;
+
 pub struct RandomQsc {
     desired_quorum_set_size: usize,
     desired_threshold: Option<usize>,
     weights: Vec<usize>,
+    rng: RefCell<StdRng>,
 }
 impl RandomQsc {
     pub fn new(
@@ -15,11 +20,17 @@ impl RandomQsc {
             desired_quorum_set_size,
             desired_threshold,
             weights: weights.unwrap_or_default(),
+            rng: RefCell::new(StdRng::from_entropy()),
         }
     }
     pub fn new_simple(desired_quorum_set_size: usize) -> Self {
         Self::new(desired_quorum_set_size, None, None)
     }
+    /// Make the choice of validators deterministic, derived from `seed`.
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.rng.replace(StdRng::seed_from_u64(seed));
+        self
+    }
 }
 impl QuorumSetConfigurator for RandomQsc {
     fn configure(&self, node_id: NodeId, fbas: &mut Fbas) -> ChangeEffect {
@@ -45,10 +56,10 @@ impl QuorumSetConfigurator for RandomQsc {
             let mut available_nodes: Vec<NodeId> =
                 (0..n).filter(|&x| !used_nodes.contains(x)).collect();
 
-            let mut rng = thread_rng();
+            let mut rng = self.rng.borrow_mut();
             for _ in current_quorum_set_size..target_quorum_set_size {
                 let &chosen_node = available_nodes
-                    .choose_weighted(&mut rng, |&node_id| {
+                    .choose_weighted(&mut *rng, |&node_id| {
                         *self.weights.get(node_id).unwrap_or(&1)
                     })
                     .unwrap();
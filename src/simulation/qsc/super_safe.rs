@@ -2,7 +2,8 @@ use super::*;
 
 /// Super simple Quorum Set Configurator priorizing FBAS safety.
 ///
-/// Creates threshold=n quorum sets containing all n nodes in the FBAS.
+/// Creates threshold=n quorum sets containing all n nodes in the FBAS. For other (relative)
+/// thresholds, see [`RelativeIdealQsc`].
 ///
 /// ```
 /// use fbas_analyzer::{Fbas, Analysis, bitset};
@@ -0,0 +1,212 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[test]
+fn update_refuses_results_with_other_schema_version() -> Result<(), Box<dyn std::error::Error>> {
+    let output_path = std::env::temp_dir().join(format!(
+        "fbas_analyzer_test_schema_version_{}.csv",
+        std::process::id()
+    ));
+    std::fs::write(
+        &output_path,
+        "label,has_quorum_intersection,result_schema_version\nold,true,0\n",
+    )?;
+    let assert = Command::cargo_bin("bulk_fbas_analyzer")?
+        .arg("test_data/correct_trivial.json")
+        .arg("-o")
+        .arg(&output_path)
+        .arg("--update")
+        .assert();
+    std::fs::remove_file(&output_path)?;
+    assert.failure().stderr(predicate::str::contains(
+        "have result schema version 0, but this version of bulk_fbas_analyzer expects version 2",
+    ));
+    Ok(())
+}
+
+#[test]
+fn top_tier_frequencies_are_summarized_by_public_key() -> Result<(), Box<dyn std::error::Error>> {
+    let frequencies_path = std::env::temp_dir().join(format!(
+        "fbas_analyzer_test_top_tier_frequencies_{}.csv",
+        std::process::id()
+    ));
+    let assert = Command::cargo_bin("bulk_fbas_analyzer")?
+        .arg("test_data/correct.json")
+        .arg("test_data/broken.json")
+        .arg("test_data/correct_trivial.json")
+        .arg("--skip-splitting-sets")
+        .arg("--top-tier-frequencies")
+        .arg(&frequencies_path)
+        .assert();
+    let frequencies = std::fs::read_to_string(&frequencies_path);
+    let _ = std::fs::remove_file(&frequencies_path);
+    assert.success();
+    assert_eq!(
+        "public_key,top_tier_count,top_tier_frequency\n\
+        GABMKJM6I25XI4K7U6XWMULOUQIQ27BCTMLS6BYYSOWKTBUXVRJSXHYQ,3,1.0\n\
+        GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH,3,1.0\n\
+        GCM6QMP3DLRPTAZW2UZPCPX2LF3SXWXKPMP3GKFZBDSF3QZGV2G5QSTK,3,1.0\n\
+        GAOO3LWBC4XF6VWRP5ESJ6IBHAISVJMSBTALHOQM2EZG7Q477UWA6L7U,1,0.3333333333333333\n",
+        frequencies?
+    );
+    Ok(())
+}
+
+#[test]
+fn fault_tolerance_columns_are_derived_from_mbs_min() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin("bulk_fbas_analyzer")?
+        .arg("test_data/stellarbeat_nodes_2019-09-17.json")
+        .arg("test_data/stellarbeat_organizations_2019-09-17.json")
+        .arg("--skip-splitting-sets")
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut lines = stdout.lines();
+    let header: Vec<&str> = lines.next().unwrap().split(',').collect();
+    let values: Vec<&str> = lines.next().unwrap().split(',').collect();
+    let column = |name: &str| values[header.iter().position(|&h| h == name).unwrap()];
+
+    assert_eq!("4", column("mbs_min"));
+    assert_eq!("3", column("fault_tolerance"));
+    assert_eq!("2", column("orgs_mbs_min"));
+    assert_eq!("1", column("orgs_fault_tolerance"));
+    Ok(())
+}
+
+/// Runs `bulk_fbas_analyzer` on a single input and returns its output row as (column, value)
+/// pairs, leaving out durations, which vary from run to run.
+fn single_output_row(args: &[&str]) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let output = Command::cargo_bin("bulk_fbas_analyzer")?
+        .args(args)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    let mut lines = stdout.lines();
+    let header = lines.next().unwrap().split(',');
+    let values = lines.next().unwrap().split(',');
+    Ok(header
+        .zip(values)
+        .filter(|(column, _)| !column.starts_with("analysis_duration"))
+        .map(|(column, value)| (column.to_string(), value.to_string()))
+        .collect())
+}
+
+/// Asserts that `skipped` equals `full` except for the `expected_changes`.
+fn assert_only_changed(
+    full: &[(String, String)],
+    skipped: &[(String, String)],
+    expected_changes: &[(&str, &str)],
+) {
+    assert_eq!(full.len(), skipped.len());
+    for ((column, full_value), (skipped_column, skipped_value)) in full.iter().zip(skipped) {
+        assert_eq!(column, skipped_column);
+        let expected_value = expected_changes
+            .iter()
+            .find(|(changed_column, _)| changed_column == column)
+            .map_or(full_value.as_str(), |(_, value)| value);
+        assert_eq!(expected_value, skipped_value, "in column {}", column);
+    }
+}
+
+#[test]
+fn skipping_quorums_only_changes_mq_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let inputs = [
+        "test_data/stellarbeat_nodes_2019-09-17.json",
+        "test_data/stellarbeat_organizations_2019-09-17.json",
+        "--skip-splitting-sets",
+    ];
+    let full = single_output_row(&inputs)?;
+    let skipped = single_output_row(&[&inputs[..], &["--skip-quorums"]].concat())?;
+    assert_only_changed(
+        &full,
+        &skipped,
+        &[
+            ("mq_min", "0"),
+            ("mq_max", "0"),
+            ("mq_mean", "0.0"),
+            ("orgs_mq_min", ""),
+            ("orgs_mq_max", ""),
+            ("orgs_mq_mean", ""),
+            ("ctries_mq_min", ""),
+            ("ctries_mq_max", ""),
+            ("ctries_mq_mean", ""),
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn skipping_blocking_sets_only_changes_mbs_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let inputs = [
+        "test_data/stellarbeat_nodes_2019-09-17.json",
+        "test_data/stellarbeat_organizations_2019-09-17.json",
+        "--skip-splitting-sets",
+    ];
+    let full = single_output_row(&inputs)?;
+    let skipped = single_output_row(&[&inputs[..], &["--skip-blocking-sets"]].concat())?;
+    assert_only_changed(
+        &full,
+        &skipped,
+        &[
+            ("mbs_min", "0"),
+            ("mbs_max", "0"),
+            ("mbs_mean", "0.0"),
+            ("fault_tolerance", ""),
+            ("orgs_mbs_min", ""),
+            ("orgs_mbs_max", ""),
+            ("orgs_mbs_mean", ""),
+            ("orgs_fault_tolerance", ""),
+            ("ctries_mbs_min", ""),
+            ("ctries_mbs_max", ""),
+            ("ctries_mbs_mean", ""),
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn skipping_splitting_sets_only_changes_mss_columns() -> Result<(), Box<dyn std::error::Error>> {
+    let inputs = ["test_data/correct_trivial.json"];
+    let full = single_output_row(&inputs)?;
+    let skipped = single_output_row(&[&inputs[..], &["--skip-splitting-sets"]].concat())?;
+    assert_only_changed(
+        &full,
+        &skipped,
+        &[("mss_min", "0"), ("mss_max", "0"), ("mss_mean", "0.0")],
+    );
+    Ok(())
+}
+
+#[test]
+fn metric_outputs_only_label_and_selected_metric() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("bulk_fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json")
+        .arg("test_data/broken_trivial.json")
+        .arg("--metric")
+        .arg("mq_min");
+    cmd.assert()
+        .success()
+        .stdout("label,mq_min\nbroken_trivial,1\ncorrect_trivial,2\n");
+
+    let mut cmd = Command::cargo_bin("bulk_fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json")
+        .arg("test_data/broken_trivial.json")
+        .arg("-m")
+        .arg("has_quorum_intersection");
+    cmd.assert()
+        .success()
+        .stdout("label,has_quorum_intersection\nbroken_trivial,false\ncorrect_trivial,true\n");
+    Ok(())
+}
+
+#[test]
+fn unknown_metric_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("bulk_fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json")
+        .arg("--metric")
+        .arg("mq_median");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown metric 'mq_median'"));
+    Ok(())
+}
@@ -66,3 +66,406 @@ fn merge_by_ctry_cli_arg_works() -> Result<(), Box<dyn std::error::Error>> {
     ));
     Ok(())
 }
+
+#[test]
+fn merge_by_home_domain_cli_arg_works() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/stellarbeat_nodes_2019-09-17.json")
+        .arg("--merge-by-home-domain")
+        .arg("-q")
+        .arg("-p")
+        .arg("--results-only");
+    cmd.assert().success().stdout(predicate::str::contains(
+        r#"top_tier: ["lobstr.co","www.stellar.org","coinqvest.com","satoshipay.io","keybase.io"]"#,
+    ));
+    Ok(())
+}
+
+#[test]
+fn internal_input_format_is_read_by_node_id() -> Result<(), Box<dyn std::error::Error>> {
+    let fbas =
+        fbas_analyzer::Fbas::from_json_file(std::path::Path::new("test_data/correct_trivial.json"));
+    let path = std::env::temp_dir().join("fbas_analyzer_internal_input_format_test.json");
+    std::fs::write(&path, fbas.to_internal_json_string())?;
+
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg(&path)
+        .arg("--input-format")
+        .arg("internal")
+        .arg("-q")
+        .arg("--results-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("has_quorum_intersection: true"))
+        .stdout(predicate::str::contains(
+            "minimal_quorums: [[0,1],[0,2],[1,2]]",
+        ));
+    Ok(())
+}
+
+#[test]
+fn internal_input_format_is_read_from_stdin() -> Result<(), Box<dyn std::error::Error>> {
+    let fbas =
+        fbas_analyzer::Fbas::from_json_file(std::path::Path::new("test_data/correct_trivial.json"));
+    Command::cargo_bin("fbas_analyzer")?
+        .write_stdin(fbas.to_internal_json_string())
+        .arg("--input-format")
+        .arg("internal")
+        .arg("--results-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("has_quorum_intersection: true"));
+    Ok(())
+}
+
+#[test]
+fn unsupported_input_format_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json")
+        .arg("--input-format")
+        .arg("stellar-toml");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Reading stellar-core TOML configs is not supported yet; please convert",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+    Ok(())
+}
+
+#[test]
+fn unknown_input_format_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json")
+        .arg("--input-format")
+        .arg("edge-list");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown input format 'edge-list'"));
+    Ok(())
+}
+
+#[test]
+fn deeply_nested_quorum_sets_are_only_warned_about_by_default(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct.json")
+        .arg("--max-nesting-depth")
+        .arg("1")
+        .arg("--results-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("has_quorum_intersection: true"));
+    Ok(())
+}
+
+#[test]
+fn deeply_nested_quorum_sets_can_be_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct.json")
+        .arg("--max-nesting-depth")
+        .arg("1")
+        .arg("--reject-deep-quorum-sets");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "nodes have quorum sets nested deeper than 1 levels.",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct.json")
+        .arg("--max-nesting-depth")
+        .arg("2")
+        .arg("--reject-deep-quorum-sets");
+    cmd.assert().success();
+    Ok(())
+}
+
+#[test]
+fn durations_can_be_reported_in_microseconds() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json")
+        .arg("-q")
+        .arg("--duration-unit")
+        .arg("us")
+        .arg("--results-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_match(r"minimal_quorums_analysis_duration: \d+us\n").unwrap());
+    Ok(())
+}
+
+#[test]
+fn unknown_duration_unit_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json")
+        .arg("--duration-unit")
+        .arg("fortnights");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Unknown duration unit 'fortnights'",
+    ));
+    Ok(())
+}
+
+#[test]
+fn top_tier_can_be_grouped_by_org() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/stellarbeat_nodes_2019-09-17.json")
+        .arg("--merge-by-org")
+        .arg("test_data/stellarbeat_organizations_2019-09-17.json")
+        .arg("--pretty-grouped")
+        .arg("-q")
+        .arg("--results-only");
+    cmd.assert().success().stdout(predicate::str::contains(
+        r#"top_tier: {"COINQVEST Limited":["#,
+    ));
+    Ok(())
+}
+
+#[test]
+fn lint_of_correct_fbas_succeeds() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json").arg("--lint");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("dangling_references: []"))
+        .stdout(predicate::str::contains("has_quorum_intersection").not());
+    Ok(())
+}
+
+#[test]
+fn lint_reports_dangling_references() -> Result<(), Box<dyn std::error::Error>> {
+    let fbas_input = r#"[
+            {
+                "publicKey": "n0",
+                "quorumSet": { "threshold": 1, "validators": ["n0", "n1"] }
+            }
+            ]"#;
+    Command::cargo_bin("fbas_analyzer")?
+        .write_stdin(fbas_input.as_bytes())
+        .arg("--lint")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            r#"dangling_references: [["n0","n1"]]"#,
+        ));
+    Ok(())
+}
+
+#[test]
+fn lint_reports_unparsable_json_without_panicking() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .write_stdin(r#"[{"publicKey": 1}]"#.as_bytes())
+        .arg("--lint")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Error parsing FBAS JSON"))
+        .stderr(predicate::str::contains("panicked").not());
+    Ok(())
+}
+
+#[test]
+fn passing_assertions_exit_successfully() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json")
+        .arg("--assert-quorum-intersection")
+        .arg("--assert-fault-tolerance")
+        .arg("1");
+    cmd.assert().success();
+    Ok(())
+}
+
+#[test]
+fn failing_assertions_have_distinct_exit_codes() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct_trivial.json")
+        .arg("--assert-fault-tolerance")
+        .arg("2")
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            "Assertion failed: the FBAS doesn't tolerate 2 failures",
+        ));
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/broken_trivial.json")
+        .arg("--assert-quorum-intersection")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Assertion failed: the FBAS lacks quorum intersection.",
+        ));
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/broken_trivial.json")
+        .arg("--assert-quorum-intersection")
+        .arg("--assert-fault-tolerance")
+        .arg("100")
+        .assert()
+        .code(6);
+    Ok(())
+}
+
+#[test]
+fn inactive_nodes_as_faulty_keeps_node_ids() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct_trivial_with_inactive_node.json")
+        .arg("-b")
+        .arg("--results-only")
+        .arg("--ignore-inactive-nodes")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "minimal_blocking_sets: [[0],[1],[2]]",
+        ));
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("test_data/correct_trivial_with_inactive_node.json")
+        .arg("-b")
+        .arg("--results-only")
+        .arg("--inactive-nodes-as-faulty")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "minimal_blocking_sets: [[1],[2],[3]]",
+        ));
+    Ok(())
+}
+
+#[cfg(not(feature = "url-input"))]
+#[test]
+fn urls_are_rejected_without_url_input_feature() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("https://api.stellarbeat.io/v1/nodes")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "requires building with the `url-input` feature (e.g., `cargo install",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+    Ok(())
+}
+
+#[cfg(feature = "url-input")]
+#[test]
+fn url_fetch_errors_are_reported_without_panicking() -> Result<(), Box<dyn std::error::Error>> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}/nodes", listener.local_addr()?);
+    drop(listener);
+    Command::cargo_bin("fbas_analyzer")?
+        .arg(&url)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("Error fetching FBAS JSON"))
+        .stderr(predicate::str::contains("panicked").not());
+    Ok(())
+}
+
+#[test]
+fn inactive_nodes_cant_be_handled_for_urls() -> Result<(), Box<dyn std::error::Error>> {
+    Command::cargo_bin("fbas_analyzer")?
+        .arg("https://api.stellarbeat.io/v1/nodes")
+        .arg("--ignore-inactive-nodes")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "not supported when fetching an FBAS from a URL; perhaps download it to a file first?",
+        ))
+        .stderr(predicate::str::contains("panicked").not());
+    Ok(())
+}
+
+#[test]
+fn describe_output_can_use_named_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json")
+        .arg("-q")
+        .arg("--describe")
+        .arg("--json")
+        .arg("--results-only");
+    cmd.assert().success().stdout(predicate::str::contains(
+        r#"minimal_quorums: {"count":3,"distinct_nodes":3,"size":{"min":2,"max":2,"mean":2.0},"histogram":[0,0,3]}"#,
+    ));
+    Ok(())
+}
+
+#[test]
+fn json_output_requires_describe() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct_trivial.json").arg("--json");
+    cmd.assert().failure();
+    Ok(())
+}
+
+#[test]
+fn top_tier_only_outputs_top_tier_and_size() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/stellarbeat_nodes_2019-09-17.json")
+        .arg("--merge-by-org")
+        .arg("test_data/stellarbeat_organizations_2019-09-17.json")
+        .arg("--top-tier-only")
+        .arg("--results-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "top_tier: [56,86,167,168,171]\ntop_tier_size: 5\n",
+        ))
+        .stdout(predicate::str::contains("minimal_quorums").not())
+        .stdout(predicate::str::contains("has_quorum_intersection").not());
+    Ok(())
+}
+
+#[test]
+fn top_tier_only_without_symmetric_top_tier() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct.json")
+        .arg("--top-tier-only")
+        .arg("--results-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("top_tier_size: 3\n"));
+    Ok(())
+}
+
+#[test]
+fn flat_output_has_one_metric_per_line() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+    cmd.arg("test_data/correct.json").arg("-qb").arg("--flat");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\nhas_quorum_intersection=true\n"))
+        .stdout(predicate::str::contains("\ntop_tier.size=3\n"))
+        .stdout(predicate::str::contains("\nminimal_quorums.count=3\n"))
+        .stdout(predicate::str::contains("\nminimal_blocking_sets.min=2\n"))
+        .stdout(predicate::str::contains(
+            "\nminimal_blocking_sets.mean=2.0\n",
+        ))
+        .stdout(predicate::str::contains("duration").not())
+        .stdout(predicate::str::is_match("(?m)^[a-z_.]+=[^ ]+$")?);
+    Ok(())
+}
+
+#[test]
+fn merge_first_yields_same_top_tier_as_merging_after() -> Result<(), Box<dyn std::error::Error>> {
+    let top_tier_lines = |merge_first: bool| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("fbas_analyzer")?;
+        cmd.arg("test_data/stellarbeat_nodes_2019-09-17.json")
+            .arg("--merge-by-org")
+            .arg("test_data/stellarbeat_organizations_2019-09-17.json")
+            .arg("--top-tier-only")
+            .arg("--pretty")
+            .arg("--results-only");
+        if merge_first {
+            cmd.arg("--merge-first");
+        }
+        let output = cmd.output()?;
+        assert!(output.status.success());
+        Ok(String::from_utf8(output.stdout)?
+            .lines()
+            .filter(|line| line.starts_with("top_tier") && !line.contains("duration"))
+            .map(String::from)
+            .collect())
+    };
+    let merged_after = top_tier_lines(false)?;
+    assert!(merged_after.contains(&String::from("top_tier_size: 5")));
+    assert_eq!(merged_after, top_tier_lines(true)?);
+    Ok(())
+}